@@ -1,12 +1,14 @@
 use crate::config::Config;
 use crate::daemon::DaemonRegistry;
 use crate::error::Result;
+use crate::paths::Paths;
+use crate::types::{Domain, DomainKind};
 use nix::unistd::{self, Uid};
 
 pub struct Angel {
     pub daemons: DaemonRegistry,
-    #[allow(dead_code)]
     pub config: Config,
+    pub paths: Paths,
     pub euid: Uid,
     pub uid: Uid,
 }
@@ -14,19 +16,29 @@ pub struct Angel {
 impl Angel {
     pub fn load() -> Result<Self> {
         let config = Config::load()?;
+        let paths = Paths::load(&config);
         let euid = unistd::geteuid();
-        // When running with sudo, getuid() may return 0. Check SUDO_UID first.
-        let uid = std::env::var("SUDO_UID")
-            .ok()
-            .and_then(|s| s.parse::<u32>().ok())
-            .map(Uid::from_raw)
-            .unwrap_or_else(|| unistd::getuid());
-        let daemons = DaemonRegistry::new(&config, uid.as_raw())?;
+        let uid = invoking_uid();
+        let daemons = DaemonRegistry::new(&config, &paths, uid.as_raw())?;
 
-        Ok(Self { daemons, config, euid, uid })
+        Ok(Self { daemons, config, paths, euid, uid })
+    }
+
+    /// The domain a `--domain` flag names for the invoking user.
+    pub fn domain(&self, kind: Option<DomainKind>) -> Option<Domain> {
+        kind.map(|kind| kind.domain(self.uid.as_raw()))
     }
 
     pub fn is_root(&self) -> bool {
         self.euid.is_root()
     }
 }
+
+/// The user angel acts for. When running with sudo, getuid() may return 0, so SUDO_UID wins.
+pub fn invoking_uid() -> Uid {
+    std::env::var("SUDO_UID")
+        .ok()
+        .and_then(|s| s.parse::<u32>().ok())
+        .map(Uid::from_raw)
+        .unwrap_or_else(unistd::getuid)
+}
@@ -1,4 +1,7 @@
-use clap::{Args, Parser, Subcommand, ValueEnum};
+use crate::commands::completions::{service_names, service_names_or_paths};
+use crate::types::DomainKind;
+use clap::{Args, Parser, Subcommand, ValueEnum, ValueHint};
+use clap_complete::engine::{ArgValueCandidates, ArgValueCompleter};
 use strum::Display;
 
 const VERSION: &str = "0.1.0";
@@ -8,8 +11,17 @@ const VERSION: &str = "0.1.0";
 #[command(about = "macOS launchd service manager", version = VERSION)]
 pub struct Cli {
     /// Verbose output
-    #[arg(short, long)]
+    #[arg(short, long, global = true)]
     pub verbose: bool,
+    /// Answer every confirmation with yes
+    #[arg(short, long, global = true)]
+    pub yes: bool,
+    /// Never prompt; confirmations take their default (also ANGEL_NONINTERACTIVE=1)
+    #[arg(long, global = true)]
+    pub no_input: bool,
+    /// Output format for list, status, print, plist, schedule and overrides list
+    #[arg(long, global = true, default_value = "table")]
+    pub format: crate::output::OutputFormat,
     #[command(subcommand)]
     pub command: Commands,
 }
@@ -17,19 +29,43 @@ pub struct Cli {
 #[derive(Args)]
 pub struct NameArgs {
     /// Service name
+    #[arg(add = ArgValueCandidates::new(service_names))]
     pub name: String,
     /// Exact match
     #[arg(short, long)]
     pub exact: bool,
+    /// Only consider services in this domain
+    #[arg(long)]
+    pub domain: Option<DomainKind>,
+}
+
+#[derive(Args)]
+pub struct BatchArgs {
+    /// Service names, or patterns with --all
+    #[arg(required = true, add = ArgValueCandidates::new(service_names))]
+    pub names: Vec<String>,
+    /// Exact match
+    #[arg(short, long)]
+    pub exact: bool,
+    /// Only consider services in this domain
+    #[arg(long)]
+    pub domain: Option<DomainKind>,
+    /// Act on every service matching the given patterns
+    #[arg(short, long)]
+    pub all: bool,
 }
 
 #[derive(Args)]
 pub struct ListArgs {
     /// Pattern to match
+    #[arg(add = ArgValueCandidates::new(service_names))]
     pub pattern: Option<String>,
     /// Exact match
     #[arg(short, long)]
     pub exact: bool,
+    /// Only consider services in this domain
+    #[arg(long)]
+    pub domain: Option<DomainKind>,
     /// Show Apple daemons
     #[arg(short = 'a', long = "apple")]
     pub show_apple: bool,
@@ -39,18 +75,46 @@ pub struct ListArgs {
     /// Show idle daemons (have no pid)
     #[arg(short = 'i', long = "idle", default_value = "false")]
     pub show_idle: bool,
+    /// Only show disabled daemons, running or not
+    #[arg(long = "disabled")]
+    pub only_disabled: bool,
     /// Field to sort by
     #[arg(short = 's', long = "sort", default_value = "name")]
     pub sort_by: crate::commands::list::SortBy,
+    /// Redraw every INTERVAL seconds, highlighting services that changed
+    #[arg(short, long, value_name = "INTERVAL", num_args = 0..=1, default_missing_value = crate::watch::DEFAULT_INTERVAL)]
+    pub watch: Option<f64>,
+}
+
+#[derive(Args)]
+pub struct StatusArgs {
+    #[command(flatten)]
+    pub target: NameArgs,
+    /// Redraw every INTERVAL seconds, highlighting fields that changed
+    #[arg(short, long, value_name = "INTERVAL", num_args = 0..=1, default_missing_value = crate::watch::DEFAULT_INTERVAL)]
+    pub watch: Option<f64>,
+}
+
+#[derive(Args)]
+pub struct UninstallArgs {
+    #[command(flatten)]
+    pub target: NameArgs,
+    /// Leave the plist file where it is
+    #[arg(short, long)]
+    pub keep_file: bool,
 }
 
 #[derive(Args)]
 pub struct InstallArgs {
     /// Path to the service file
+    #[arg(value_hint = ValueHint::FilePath)]
     pub path: String,
     /// Make a hard copy of the file instead of a symlink
     #[arg(short, long, default_value = "symlink")]
     pub strategy: crate::commands::install::InstallStrategy,
+    /// Domain to install into instead of asking
+    #[arg(short, long)]
+    pub domain: Option<DomainKind>,
 }
 
 #[derive(Clone, ValueEnum, Display)]
@@ -68,37 +132,203 @@ pub enum Signal {
 
 #[derive(Args)]
 pub struct StartArgs {
+    #[command(flatten)]
+    pub targets: BatchArgs,
+    /// Kill existing instance before starting
+    #[arg(short, long)]
+    pub kill: bool,
+}
+
+#[derive(Args)]
+pub struct StopArgs {
+    #[command(flatten)]
+    pub targets: BatchArgs,
+    /// Signal to send
+    #[arg(short, long, default_value = "sigterm")]
+    pub signal: Signal,
+    /// Force kill (equivalent to --signal sigkill)
+    #[arg(short, long)]
+    pub force: bool,
+}
+
+#[derive(Args)]
+pub struct LogsArgs {
     /// Service name
+    #[arg(add = ArgValueCandidates::new(service_names))]
     pub name: String,
     /// Exact match
     #[arg(short, long)]
     pub exact: bool,
-    /// Kill existing instance before starting
+    /// Only consider services in this domain
+    #[arg(long)]
+    pub domain: Option<DomainKind>,
+    /// Keep printing new lines as they are written
     #[arg(short, long)]
-    pub kill: bool,
+    pub follow: bool,
+    /// Number of lines to show from the end of each file
+    #[arg(short = 'n', long, default_value = "10")]
+    pub lines: usize,
+    /// Only show StandardOutPath
+    #[arg(long, conflicts_with = "stderr")]
+    pub stdout: bool,
+    /// Only show StandardErrorPath
+    #[arg(long)]
+    pub stderr: bool,
+    /// Prefix lines with their stream instead of printing a header per file
+    #[arg(short, long)]
+    pub interleave: bool,
 }
 
 #[derive(Args)]
-pub struct StopArgs {
+pub struct NewArgs {
+    /// Label of the new service
+    pub label: String,
+    /// Program to run
+    #[arg(short, long)]
+    pub program: Option<String>,
+    /// Arguments passed to the program
+    #[arg(short, long = "args", num_args = 1..)]
+    pub arguments: Vec<String>,
+    /// Environment variable to set (KEY=VALUE, repeatable)
+    #[arg(long, value_parser = crate::commands::new::parse_env)]
+    pub env: Vec<(String, String)>,
+    /// Start the service as soon as it is loaded
+    #[arg(long)]
+    pub run_at_load: bool,
+    /// Restart the service whenever it exits
+    #[arg(long)]
+    pub keep_alive: bool,
+    /// Run the service every N seconds
+    #[arg(long)]
+    pub interval: Option<i32>,
+    /// Run the service on a calendar schedule, e.g. Hour=9,Minute=0 (repeatable)
+    #[arg(long)]
+    pub calendar: Vec<crate::types::CalendarInterval>,
+    /// Run the service on a cron schedule, e.g. "30 2 * * 1-5" (repeatable)
+    #[arg(long)]
+    pub cron: Vec<crate::schedule::Cron>,
+    /// File to write the service's stdout to
+    #[arg(long)]
+    pub stdout: Option<String>,
+    /// File to write the service's stderr to
+    #[arg(long)]
+    pub stderr: Option<String>,
+    /// Working directory of the service
+    #[arg(long)]
+    pub workdir: Option<String>,
+    /// Domain to create the service in
+    #[arg(short, long)]
+    pub domain: Option<DomainKind>,
+    /// Bootstrap the service after writing it
+    #[arg(short, long)]
+    pub bootstrap: bool,
+}
+
+#[derive(Args)]
+pub struct LintArgs {
+    /// Plist path or service name. Lints every non-Apple plist when omitted
+    #[arg(add = ArgValueCompleter::new(service_names_or_paths))]
+    pub target: Option<String>,
+    /// Exact match
+    #[arg(short, long)]
+    pub exact: bool,
+    /// Only consider services in this domain
+    #[arg(long)]
+    pub domain: Option<DomainKind>,
+}
+
+#[derive(Args)]
+pub struct EditArgs {
     /// Service name
+    #[arg(add = ArgValueCandidates::new(service_names))]
     pub name: String,
     /// Exact match
     #[arg(short, long)]
     pub exact: bool,
-    /// Signal to send
-    #[arg(short, long, default_value = "sigterm")]
-    pub signal: Signal,
-    /// Force kill (equivalent to --signal sigkill)
+    /// Only consider services in this domain
+    #[arg(long)]
+    pub domain: Option<DomainKind>,
+    /// Reload a loaded service after saving without asking
     #[arg(short, long)]
-    pub force: bool,
+    pub reload: bool,
+}
+
+#[derive(Args)]
+pub struct ApplyArgs {
+    /// Path to the manifest
+    #[arg(value_hint = ValueHint::FilePath)]
+    pub manifest: String,
+    /// Show what would change without touching anything
+    #[arg(short = 'n', long)]
+    pub dry_run: bool,
+    /// Boot out and remove services this manifest applied before but no longer lists
+    #[arg(short, long)]
+    pub prune: bool,
+}
+
+#[derive(Args)]
+pub struct OverridesArgs {
+    #[command(subcommand)]
+    pub command: OverridesCommand,
+}
+
+#[derive(Subcommand)]
+pub enum OverridesCommand {
+    /// List every override in launchd's enable/disable database
+    #[command(alias = "ls")]
+    List(OverridesListArgs),
+    /// Remove a service's override, leaving it to the plist's Disabled key
+    Clear(NameArgs),
+}
+
+#[derive(Args)]
+pub struct OverridesListArgs {
+    /// Pattern to match
+    #[arg(add = ArgValueCandidates::new(service_names))]
+    pub pattern: Option<String>,
+    /// Exact match
+    #[arg(short, long)]
+    pub exact: bool,
+    /// Only consider overrides for this domain
+    #[arg(long)]
+    pub domain: Option<DomainKind>,
+}
+
+#[derive(Args)]
+pub struct ScheduleArgs {
+    /// Only show services matching this pattern
+    #[arg(add = ArgValueCandidates::new(service_names))]
+    pub pattern: Option<String>,
+    /// Exact match
+    #[arg(short, long)]
+    pub exact: bool,
+    /// Only consider services in this domain
+    #[arg(long)]
+    pub domain: Option<DomainKind>,
+    /// Upcoming runs to show per service
+    #[arg(short = 'n', long, default_value = "3")]
+    pub count: usize,
+    /// Include Apple services
+    #[arg(short = 'a', long = "apple")]
+    pub show_apple: bool,
+}
+
+#[derive(Args)]
+pub struct CompletionsArgs {
+    /// Shell to generate the completion script for
+    pub shell: crate::commands::completions::Shell,
 }
 
 #[derive(Subcommand)]
 pub enum Commands {
+    /// Create a new service plist
+    New(NewArgs),
     /// Install a service
     Install(InstallArgs),
+    /// Bring services in line with a manifest
+    Apply(ApplyArgs),
     /// Uninstall a service
-    Uninstall(NameArgs),
+    Uninstall(UninstallArgs),
     /// Start a service
     #[command(alias = "kickstart")]
     Start(StartArgs),
@@ -107,24 +337,40 @@ pub enum Commands {
     Stop(StopArgs),
     /// Restart a service
     #[command(alias = "kkill")]
-    Restart(NameArgs),
+    Restart(BatchArgs),
     /// Bootstrap a service
-    Bootstrap(NameArgs),
+    Bootstrap(BatchArgs),
     /// Bootout a service
-    Bootout(NameArgs),
+    Bootout(BatchArgs),
     /// Show service status
-    Status(NameArgs),
+    Status(StatusArgs),
     /// List services
     #[command(alias = "ls")]
     List(ListArgs),
     /// print a service's plist
     Plist(NameArgs),
+    /// Edit a service's plist in $EDITOR
+    Edit(EditArgs),
     /// Enable a service
-    Enable(NameArgs),
+    Enable(BatchArgs),
     /// Disable a service
-    Disable(NameArgs),
+    Disable(BatchArgs),
+    /// Show or clear launchd's enable/disable overrides
+    Overrides(OverridesArgs),
     /// Print service information (launchctl print wrapper)
     Print(NameArgs),
+    /// Compare a service's plist with the job launchd is running
+    Diff(NameArgs),
+    /// Browse and control services in a full-screen view
+    Tui,
+    /// Show upcoming runs of every periodic service
+    Schedule(ScheduleArgs),
+    /// Show a service's stdout and stderr logs
+    Logs(LogsArgs),
+    /// Check plists for common mistakes
+    Lint(LintArgs),
+    /// Print a shell completion script, e.g. `source <(angel completions bash)`
+    Completions(CompletionsArgs),
     /// Show version
     Version,
 }
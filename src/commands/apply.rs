@@ -0,0 +1,461 @@
+use crate::angel::Angel;
+use crate::cli::ApplyArgs;
+use crate::error::{Result, SystemError, UserError};
+use crate::launchctl;
+use crate::manifest::Manifest;
+use crate::output::stdout;
+use crate::styles::styles;
+use crate::types::{Daemon, Domain, ForWhom, Plist};
+use crossterm::style::Color;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Change {
+    Create,
+    Update,
+    /// Unchanged on disk but not loaded
+    Load,
+    Unchanged,
+    /// Applied by this manifest before, gone from it now
+    Remove,
+}
+
+impl Change {
+    fn describe(self) -> String {
+        match self {
+            Change::Create => styles::prefix(Color::Green, "create"),
+            Change::Update => styles::prefix(Color::Yellow, "update"),
+            Change::Load => styles::prefix(Color::Cyan, "load"),
+            Change::Unchanged => styles::command("unchanged"),
+            Change::Remove => styles::prefix(Color::Red, "remove"),
+        }
+    }
+}
+
+struct Step {
+    domain: Domain,
+    label: String,
+    path: PathBuf,
+    plist: Option<Plist>,
+    change: Change,
+    loaded: bool,
+}
+
+impl Step {
+    fn target(&self) -> String {
+        format!("{}/{}", self.domain, self.label)
+    }
+}
+
+/// What each manifest applied last time, keyed by the manifest's canonical path.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct ApplyState {
+    manifests: BTreeMap<String, Vec<AppliedService>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct AppliedService {
+    /// Fully qualified, e.g. `gui/501/com.foo`
+    target: String,
+    path: PathBuf,
+}
+
+pub fn run(angel: &Angel, args: &ApplyArgs) -> Result<()> {
+    let manifest_path = PathBuf::from(&args.manifest).canonicalize()?;
+    let manifest = Manifest::load(&manifest_path)?;
+    let manifest_key = manifest_path.display().to_string();
+    let mut state = load_state(angel)?;
+
+    let mut steps = plan(angel, &manifest)?;
+    if args.prune {
+        let previous = state.manifests.get(&manifest_key).cloned().unwrap_or_default();
+        steps.extend(plan_prune(angel, &previous, &steps));
+    }
+    print_plan(&steps);
+
+    if args.dry_run {
+        return Ok(());
+    }
+    let touches_system =
+        steps.iter().any(|step| step.domain == Domain::System && step.change != Change::Unchanged);
+    if touches_system && !angel.is_root() {
+        return Err(UserError::RequiresRoot.into());
+    }
+
+    let mut succeeded = Vec::with_capacity(steps.len());
+    for step in &steps {
+        let result = execute(angel, step);
+        if let Err(e) = &result {
+            stdout::error(&format!("{}/{}: {}", step.domain, step.label, e));
+        }
+        succeeded.push(result.is_ok());
+    }
+    let failed = succeeded.iter().filter(|ok| !**ok).count();
+
+    let previous = state.manifests.remove(&manifest_key).unwrap_or_default();
+    let applied = next_applied(&previous, &steps, &succeeded, args.prune);
+    state.manifests.insert(manifest_key, applied);
+    save_state(angel, &state)?;
+
+    match failed {
+        0 => {
+            stdout::success(&format!("applied {}", manifest_path.display()));
+            Ok(())
+        }
+        failed => Err(UserError::BatchFailed { failed, total: steps.len() }.into()),
+    }
+}
+
+fn plan(angel: &Angel, manifest: &Manifest) -> Result<Vec<Step>> {
+    let uid = angel.uid.as_raw();
+    manifest
+        .services
+        .iter()
+        .map(|service| {
+            let domain = service.domain().domain(uid);
+            let plist = service.to_plist()?;
+            let path = angel
+                .paths
+                .angel_dir(&domain)
+                .map(|dir| dir.join(format!("{}.plist", service.label)))
+                .ok_or_else(|| {
+                    UserError::InvalidArgument(
+                        "Could not determine user home directory".to_string(),
+                    )
+                })?;
+            let current = std::fs::read(&path).ok().map(|bytes| plist::from_bytes::<Plist>(&bytes));
+            let loaded = is_loaded(angel, &domain, &service.label);
+            let change = match current {
+                None => Change::Create,
+                Some(Ok(current)) if current == plist => match loaded {
+                    true => Change::Unchanged,
+                    false => Change::Load,
+                },
+                Some(_) => Change::Update,
+            };
+            Ok(Step {
+                domain,
+                label: service.label.clone(),
+                path,
+                plist: Some(plist),
+                change,
+                loaded,
+            })
+        })
+        .collect()
+}
+
+fn plan_prune(angel: &Angel, previous: &[AppliedService], steps: &[Step]) -> Vec<Step> {
+    previous
+        .iter()
+        .filter_map(|applied| {
+            let (domain, label) = Domain::parse_target(&applied.target)?;
+            let still_managed =
+                steps.iter().any(|step| step.domain == domain && step.label == label);
+            let loaded = is_loaded(angel, &domain, label);
+            match still_managed || (!loaded && !applied.path.exists()) {
+                true => None,
+                false => Some(Step {
+                    label: label.to_string(),
+                    loaded,
+                    domain,
+                    path: applied.path.clone(),
+                    plist: None,
+                    change: Change::Remove,
+                }),
+            }
+        })
+        .collect()
+}
+
+/// What to record for a manifest after a run: every service it defines now, plus anything it
+/// applied before that may still be on the system. A dropped service is only forgotten once
+/// `--prune` removed it, or found nothing left to remove.
+fn next_applied(
+    previous: &[AppliedService],
+    steps: &[Step],
+    succeeded: &[bool],
+    prune: bool,
+) -> Vec<AppliedService> {
+    let mut applied: Vec<AppliedService> = steps
+        .iter()
+        .filter(|step| step.change != Change::Remove)
+        .map(|step| AppliedService { target: step.target(), path: step.path.clone() })
+        .collect();
+    for entry in previous {
+        if applied.iter().any(|service| service.target == entry.target) {
+            continue;
+        }
+        let removal = steps
+            .iter()
+            .zip(succeeded)
+            .find(|(step, _)| step.change == Change::Remove && step.target() == entry.target);
+        let keep = match removal {
+            Some((_, removed)) => !removed,
+            None => !prune,
+        };
+        if keep {
+            applied.push(entry.clone());
+        }
+    }
+    applied
+}
+
+fn print_plan(steps: &[Step]) {
+    let mut table = styles::create_table();
+    table.set_header(vec!["Domain", "Name", "Change"]);
+    for step in steps {
+        table.add_row(vec![step.domain.to_string(), step.label.clone(), step.change.describe()]);
+    }
+    stdout::writeln(&table);
+}
+
+fn execute(angel: &Angel, step: &Step) -> Result<()> {
+    let uid = angel.uid.as_raw();
+    match (step.change, &step.plist) {
+        (Change::Unchanged, _) => Ok(()),
+        (Change::Remove, _) => {
+            let daemon = Daemon::new(
+                step.label.clone(),
+                Some(step.path.clone()),
+                step.domain.clone(),
+                ForWhom::Angel,
+                None,
+                None,
+                None,
+            );
+            if step.loaded {
+                check(launchctl::bootout(&daemon)?, "bootout")?;
+            }
+            if step.path.exists() {
+                std::fs::remove_file(&step.path)?;
+            }
+            stdout::success(&format!("removed {}", daemon.name));
+            Ok(())
+        }
+        (change, Some(plist)) => {
+            if change != Change::Load {
+                if let Some(parent) = step.path.parent() {
+                    std::fs::create_dir_all(parent)?;
+                }
+                plist::to_file_xml(&step.path, plist)?;
+            }
+            let daemon = Daemon::from_plist(
+                plist.clone(),
+                Some(step.path.clone()),
+                step.domain.clone(),
+                ForWhom::Angel,
+                uid,
+            );
+            if step.loaded {
+                check(launchctl::bootout(&daemon)?, "bootout")?;
+            }
+            check(launchctl::bootstrap(&daemon)?, "bootstrap")?;
+            stdout::success(&format!("applied {}", daemon.name));
+            Ok(())
+        }
+        (_, None) => Ok(()),
+    }
+}
+
+fn check(result: launchctl::LaunchctlResult, action: &str) -> Result<()> {
+    match result.success() {
+        true => Ok(()),
+        false => {
+            Err(UserError::ActionFailed(format!("{} failed: {}", action, result.stderr.trim()))
+                .into())
+        }
+    }
+}
+
+fn is_loaded(angel: &Angel, domain: &Domain, label: &str) -> bool {
+    angel
+        .daemons
+        .domain_info(domain)
+        .is_some_and(|info| info.services.iter().any(|service| service.label == label))
+}
+
+fn load_state(angel: &Angel) -> Result<ApplyState> {
+    let Some(path) = angel.paths.apply_state() else { return Ok(ApplyState::default()) };
+    if !path.exists() {
+        return Ok(ApplyState::default());
+    }
+    let content = std::fs::read_to_string(&path)?;
+    serde_yaml::from_str(&content).map_err(|e| {
+        SystemError::Config(anyhow::anyhow!("Failed to parse {}: {}", path.display(), e)).into()
+    })
+}
+
+fn save_state(angel: &Angel, state: &ApplyState) -> Result<()> {
+    let Some(path) = angel.paths.apply_state() else { return Ok(()) };
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let content =
+        serde_yaml::to_string(state).map_err(|e| SystemError::Serialize(e.to_string()))?;
+    std::fs::write(&path, content)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::daemon::DaemonRegistry;
+    use crate::launchctl::{FakeLaunchctl, FakeService};
+    use crate::manifest::ServiceSpec;
+    use crate::paths::Paths;
+    use nix::unistd::Uid;
+    use std::sync::{Arc, Mutex};
+
+    /// The launchctl backend is process-wide, so tests that load a registry take turns.
+    static SERIAL: Mutex<()> = Mutex::new(());
+
+    const UID: u32 = 501;
+
+    /// A scratch root with `loaded` services in `gui/501`, and an `Angel` that sees them.
+    fn with_angel(name: &str, loaded: &[&str], test: impl FnOnce(&Angel)) {
+        let _serial = SERIAL.lock().unwrap_or_else(|e| e.into_inner());
+        let root =
+            std::env::temp_dir().join(format!("angel-apply-{}-{}", std::process::id(), name));
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(&root).unwrap();
+
+        let fake = FakeLaunchctl::new().with_domain("gui/501").with_domain("user/501");
+        for label in loaded {
+            let service = FakeService { label: label.to_string(), ..Default::default() };
+            fake.add_service("gui/501", service);
+        }
+        launchctl::set_backend(Arc::new(fake));
+
+        let config = Config { root: None, directories: None, colors: None };
+        let paths = Paths::new(&root, Some(PathBuf::from("/home/tester")));
+        let daemons = DaemonRegistry::new(&config, &paths, UID).unwrap();
+        let uid = Uid::from_raw(UID);
+        test(&Angel { daemons, config, paths, euid: uid, uid });
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    fn spec(label: &str) -> ServiceSpec {
+        ServiceSpec {
+            label: label.to_string(),
+            program: Some("/usr/bin/true".to_string()),
+            ..Default::default()
+        }
+    }
+
+    fn write_plist(angel: &Angel, spec: &ServiceSpec) -> PathBuf {
+        let dir = angel.paths.angel_dir(&Domain::Gui(UID)).unwrap();
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(format!("{}.plist", spec.label));
+        plist::to_file_xml(&path, &spec.to_plist().unwrap()).unwrap();
+        path
+    }
+
+    fn applied(target: &str, path: &str) -> AppliedService {
+        AppliedService { target: target.to_string(), path: PathBuf::from(path) }
+    }
+
+    fn step(target: &str, change: Change) -> Step {
+        let (domain, label) = Domain::parse_target(target).unwrap();
+        Step {
+            label: label.to_string(),
+            domain,
+            path: PathBuf::from(format!("/{}.plist", label)),
+            plist: None,
+            change,
+            loaded: false,
+        }
+    }
+
+    fn targets(services: &[AppliedService]) -> Vec<&str> {
+        services.iter().map(|service| service.target.as_str()).collect()
+    }
+
+    #[test]
+    fn plan_compares_disk_and_runtime() {
+        with_angel("plan", &["com.example.unchanged"], |angel| {
+            let unchanged = spec("com.example.unchanged");
+            let load = spec("com.example.load");
+            let mut update = spec("com.example.update");
+            write_plist(angel, &unchanged);
+            write_plist(angel, &load);
+            write_plist(angel, &update);
+            update.run_at_load = true;
+            let manifest =
+                Manifest { services: vec![spec("com.example.create"), unchanged, load, update] };
+
+            let steps = plan(angel, &manifest).unwrap();
+            let changes: Vec<_> =
+                steps.iter().map(|step| (step.label.as_str(), step.change, step.loaded)).collect();
+            assert_eq!(
+                changes,
+                [
+                    ("com.example.create", Change::Create, false),
+                    ("com.example.unchanged", Change::Unchanged, true),
+                    ("com.example.load", Change::Load, false),
+                    ("com.example.update", Change::Update, false),
+                ]
+            );
+            let gui = angel.paths.angel_dir(&Domain::Gui(UID)).unwrap();
+            assert_eq!(steps[0].path, gui.join("com.example.create.plist"));
+            assert!(steps.iter().all(|step| step.domain == Domain::Gui(UID)));
+        });
+    }
+
+    #[test]
+    fn plan_prune_removes_only_what_is_left() {
+        with_angel("prune", &["com.example.loaded"], |angel| {
+            let on_disk = write_plist(angel, &spec("com.example.on-disk"));
+            let previous = [
+                applied("gui/501/com.example.kept", "/nonexistent/kept.plist"),
+                applied("gui/501/com.example.loaded", "/nonexistent/loaded.plist"),
+                applied("gui/501/com.example.on-disk", on_disk.to_str().unwrap()),
+                applied("gui/501/com.example.gone", "/nonexistent/gone.plist"),
+                applied("not a target", "/nonexistent/bad.plist"),
+            ];
+            let steps =
+                plan(angel, &Manifest { services: vec![spec("com.example.kept")] }).unwrap();
+
+            let removals = plan_prune(angel, &previous, &steps);
+            let removals: Vec<_> = removals
+                .iter()
+                .map(|step| (step.target(), step.change, step.loaded, step.plist.is_none()))
+                .collect();
+            assert_eq!(
+                removals,
+                [
+                    ("gui/501/com.example.loaded".to_string(), Change::Remove, true, true),
+                    ("gui/501/com.example.on-disk".to_string(), Change::Remove, false, true),
+                ]
+            );
+        });
+    }
+
+    #[test]
+    fn next_applied_keeps_dropped_services_without_prune() {
+        let previous = [applied("gui/501/a", "/a.plist"), applied("gui/501/b", "/b.plist")];
+        let steps = [step("gui/501/a", Change::Unchanged)];
+        let next = next_applied(&previous, &steps, &[true], false);
+        assert_eq!(targets(&next), ["gui/501/a", "gui/501/b"]);
+    }
+
+    #[test]
+    fn next_applied_forgets_only_successful_removals() {
+        let previous = [
+            applied("gui/501/removed", "/removed.plist"),
+            applied("gui/501/failed", "/failed.plist"),
+            applied("gui/501/gone", "/gone.plist"),
+        ];
+        let steps = [
+            step("gui/501/new", Change::Create),
+            step("gui/501/removed", Change::Remove),
+            step("gui/501/failed", Change::Remove),
+        ];
+        let next = next_applied(&previous, &steps, &[false, true, false], true);
+        assert_eq!(targets(&next), ["gui/501/new", "gui/501/failed"]);
+        assert_eq!(next[1].path, PathBuf::from("/failed.plist"));
+    }
+}
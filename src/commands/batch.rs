@@ -0,0 +1,96 @@
+use crate::angel::Angel;
+use crate::cli::BatchArgs;
+use crate::error::{Result, UserError};
+use crate::launchctl::LaunchctlResult;
+use crate::output::{is_verbose, stdout};
+use crate::styles::styles;
+use crate::types::Daemon;
+use crossterm::style::Color;
+
+/// Verbs for one launchctl action, e.g. `("start", "started")`.
+pub struct Action {
+    pub verb: &'static str,
+    pub past: &'static str,
+}
+
+/// Resolve every name to a service. With `--all` each name is a pattern and every match is
+/// taken; otherwise each name resolves to one service, prompting when several match.
+pub fn resolve<'a>(angel: &'a Angel, args: &BatchArgs) -> Result<Vec<&'a Daemon>> {
+    let domain = angel.domain(args.domain);
+    let mut daemons: Vec<&Daemon> = Vec::new();
+    for name in &args.names {
+        let matches = match args.all {
+            true => {
+                let mut matches = angel.daemons.get_matches(name, args.exact, domain.as_ref())?;
+                if matches.is_empty() {
+                    return Err(UserError::DaemonNotFound(name.clone()).into());
+                }
+                matches.sort_by(|a, b| a.name.cmp(&b.name));
+                matches
+            }
+            false => vec![angel.daemons.get_match(name, args.exact, domain.as_ref())?],
+        };
+        for daemon in matches {
+            if !daemons.iter().any(|seen| std::ptr::eq(*seen, daemon)) {
+                daemons.push(daemon);
+            }
+        }
+    }
+    Ok(daemons)
+}
+
+/// Run `op` on every resolved service. A single service reports inline as before; several get
+/// a summary table. Fails if any service failed.
+pub fn run<F>(angel: &Angel, args: &BatchArgs, action: Action, op: F) -> Result<()>
+where
+    F: Fn(&Daemon) -> Result<LaunchctlResult>,
+{
+    let daemons = resolve(angel, args)?;
+    if let [daemon] = daemons.as_slice() {
+        let result = op(daemon)?;
+        stdout::write(&result.output);
+        return match result.success() {
+            true => {
+                stdout::success(&format!("{} {}", action.past, daemon.name));
+                Ok(())
+            }
+            false => Err(UserError::ActionFailed(format!(
+                "failed to {} {}: {}",
+                action.verb,
+                daemon.name,
+                result.stderr.trim()
+            ))
+            .into()),
+        };
+    }
+
+    let mut table = styles::create_table();
+    table.set_header(vec!["Domain", "Name", "Result"]);
+    let mut failed = 0;
+    for daemon in &daemons {
+        let outcome = match op(daemon) {
+            Ok(result) if result.success() => {
+                if is_verbose() {
+                    stdout::write(&result.output);
+                }
+                Ok(())
+            }
+            Ok(result) => Err(result.stderr.trim().to_string()),
+            Err(e) => Err(e.to_string()),
+        };
+        let cell = match outcome {
+            Ok(()) => format!("{} {}", styles::prefix(Color::Green, "✔"), action.past),
+            Err(reason) => {
+                failed += 1;
+                format!("{} {}", styles::prefix(Color::Red, "✘"), reason)
+            }
+        };
+        table.add_row(vec![daemon.domain.to_string(), daemon.name.clone(), cell]);
+    }
+    stdout::writeln(&table);
+
+    match failed {
+        0 => Ok(()),
+        failed => Err(UserError::BatchFailed { failed, total: daemons.len() }.into()),
+    }
+}
@@ -1,19 +1,10 @@
 use crate::angel::Angel;
-use crate::cli::NameArgs;
+use crate::cli::BatchArgs;
+use crate::commands::batch::{self, Action};
 use crate::error::Result;
 use crate::launchctl;
-use crate::output::stdout;
 
-pub fn run(angel: &Angel, args: &NameArgs) -> Result<()> {
-    let daemon = angel.daemons.get_match(&args.name, args.exact)?;
-    let result = launchctl::bootstrap(daemon)?;
-    stdout::write(&result.output);
-    match result.success() {
-        true => stdout::success(&format!("bootstrapped {}", daemon.name)),
-        false => stdout::error(&format!(
-            "failed to bootstrap {}: {}",
-            daemon.name, result.stderr
-        )),
-    }
-    Ok(())
+pub fn run(angel: &Angel, args: &BatchArgs) -> Result<()> {
+    let action = Action { verb: "bootstrap", past: "bootstrapped" };
+    batch::run(angel, args, action, launchctl::bootstrap)
 }
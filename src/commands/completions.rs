@@ -0,0 +1,90 @@
+use crate::angel::{Angel, invoking_uid};
+use crate::cli::CompletionsArgs;
+use crate::config::Config;
+use crate::daemon::get_plist_dirs;
+use crate::error::Result;
+use crate::paths::Paths;
+use crate::types::Plist;
+use clap::ValueEnum;
+use clap_complete::engine::{CompletionCandidate, PathCompleter, ValueCompleter};
+use clap_complete::env::Shells;
+use std::collections::{BTreeMap, BTreeSet};
+use std::ffi::OsStr;
+use std::path::PathBuf;
+
+/// Environment variable the generated scripts set when they call back into angel for
+/// candidates, as `COMPLETE=<shell> angel -- <words>`.
+pub const COMPLETE_ENV: &str = "COMPLETE";
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+}
+
+impl Shell {
+    fn name(self) -> &'static str {
+        match self {
+            Shell::Bash => "bash",
+            Shell::Zsh => "zsh",
+            Shell::Fish => "fish",
+        }
+    }
+}
+
+pub fn run(_angel: &Angel, args: &CompletionsArgs) -> Result<()> {
+    let shells = Shells::builtins();
+    let Some(shell) = shells.completer(args.shell.name()) else { return Ok(()) };
+    let mut out = std::io::stdout();
+    shell.write_registration(COMPLETE_ENV, "angel", "angel", &completer()?, &mut out)?;
+    Ok(())
+}
+
+/// How the script should invoke angel: as typed when found on `PATH`, otherwise by absolute
+/// path, the same way clap resolves it for `COMPLETE=<shell> angel`.
+fn completer() -> Result<String> {
+    let invoked = PathBuf::from(std::env::args_os().next().unwrap_or_else(|| "angel".into()));
+    let completer = match invoked.components().count() > 1 {
+        true => std::env::current_dir()?.join(invoked),
+        false => invoked,
+    };
+    Ok(completer.to_string_lossy().into_owned())
+}
+
+/// Every service label with a plist angel knows about, for completing service-name arguments.
+/// Runs on every Tab, so it only scans plist directories: no launchctl calls, no warnings, and
+/// any failure just means fewer candidates.
+pub fn service_names() -> Vec<CompletionCandidate> {
+    let Ok(config) = Config::load() else { return Vec::new() };
+    let paths = Paths::load(&config);
+
+    // one candidate per label, listing every domain it's defined in
+    let mut labels: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+    for dir in get_plist_dirs(&config, &paths, invoking_uid().as_raw()) {
+        let pattern = format!("{}/*.plist", dir.path.display());
+        let Ok(entries) = glob::glob(&pattern) else { continue };
+        for entry in entries.flatten() {
+            let Ok(Plist { label: Some(label), .. }) = plist::from_file::<_, Plist>(&entry) else {
+                continue;
+            };
+            labels.entry(label).or_default().insert(dir.domain.to_string());
+        }
+    }
+    labels
+        .into_iter()
+        .map(|(label, domains)| {
+            let domains: Vec<String> = domains.into_iter().collect();
+            CompletionCandidate::new(label).help(Some(domains.join(", ").into()))
+        })
+        .collect()
+}
+
+/// Service labels plus files, for arguments that take either.
+pub fn service_names_or_paths(current: &OsStr) -> Vec<CompletionCandidate> {
+    let prefix = current.to_string_lossy();
+    let mut candidates = service_names();
+    candidates.retain(|candidate| candidate.get_value().to_string_lossy().starts_with(&*prefix));
+    candidates.extend(PathCompleter::any().complete(current));
+    candidates
+}
@@ -0,0 +1,261 @@
+use crate::angel::Angel;
+use crate::cli::NameArgs;
+use crate::error::{Result, UserError};
+use crate::output::stdout;
+use crate::parser::Parser;
+use crate::styles::styles;
+use crate::types::{KeepAlive, Plist, ServiceInfo};
+use crossterm::style::Color;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// launchd adds this to every job's environment itself
+const IMPLICIT_ENVIRONMENT: [&str; 1] = ["XPC_SERVICE_NAME"];
+
+/// One field as the plist on disk has it and as launchd is running it.
+struct Difference {
+    field: String,
+    on_disk: Option<String>,
+    running: Option<String>,
+}
+
+pub fn run(angel: &Angel, args: &NameArgs) -> Result<()> {
+    let daemon =
+        angel.daemons.get_match(&args.name, args.exact, angel.domain(args.domain).as_ref())?;
+    let source_path = daemon.source_path.as_ref().ok_or_else(|| {
+        UserError::InvalidArgument(format!("{} has no plist file on disk", daemon.name))
+    })?;
+    // re-read rather than use the registry's copy, which predates any edit made since
+    let plist = plist::from_bytes::<Plist>(&std::fs::read(source_path)?)?;
+    let Some(info) = Parser::parse_print_service(daemon)? else {
+        stdout::hint(&format!("{} is not loaded, nothing to compare against", daemon.name));
+        return Ok(());
+    };
+
+    let differences = compare(&plist, &info);
+    if differences.is_empty() {
+        stdout::success(&format!("launchd is running the definition in {}", source_path.display()));
+        return Ok(());
+    }
+
+    stdout::writeln(styles::prefix(Color::Red, &format!("--- {}", source_path.display())));
+    stdout::writeln(styles::prefix(Color::Green, &format!("+++ launchd {}", info.target)));
+    let width = differences.iter().map(|d| d.field.len()).max().unwrap_or(0);
+    for difference in &differences {
+        if let Some(on_disk) = &difference.on_disk {
+            let line = format!("- {:<width$}  {}", difference.field, on_disk);
+            stdout::writeln(styles::prefix(Color::Red, &line));
+        }
+        if let Some(running) = &difference.running {
+            let line = format!("+ {:<width$}  {}", difference.field, running);
+            stdout::writeln(styles::prefix(Color::Green, &line));
+        }
+    }
+    stdout::hint(&format!(
+        "Reload needed: launchd is running an older definition of {}. Run `angel bootout {}` \
+         then `angel bootstrap {}`.",
+        daemon.name, info.target, info.target
+    ));
+    Ok(())
+}
+
+fn compare(plist: &Plist, info: &ServiceInfo) -> Vec<Difference> {
+    let mut differences = Vec::new();
+    let mut scalar = |field: &str, on_disk: Option<String>, running: Option<String>| {
+        if on_disk != running {
+            differences.push(Difference { field: field.to_string(), on_disk, running });
+        }
+    };
+
+    // launchd reports argv[0] as the program for ProgramArguments-only jobs
+    let disk_arguments = match &plist.program_arguments {
+        Some(arguments) => arguments.clone(),
+        None => plist.program.iter().cloned().collect(),
+    };
+    let disk_program = plist.program.clone().or_else(|| disk_arguments.first().cloned());
+    let running_arguments = match info.arguments.is_empty() {
+        true => info.program.iter().cloned().collect(),
+        false => info.arguments.clone(),
+    };
+    scalar("program", disk_program, info.program.clone());
+    scalar("arguments", Some(quote(&disk_arguments)), Some(quote(&running_arguments)));
+    scalar("working directory", plist.working_directory.clone(), info.working_directory.clone());
+    scalar("stdout path", plist.standard_out_path.clone(), info.stdout_path.clone());
+    scalar("stderr path", plist.standard_error_path.clone(), info.stderr_path.clone());
+
+    let keep_alive = match &plist.keep_alive {
+        Some(KeepAlive::Always(always)) => *always,
+        Some(KeepAlive::Conditions(_)) => true,
+        None => false,
+    };
+    scalar(
+        "keep alive",
+        Some(keep_alive.to_string()),
+        Some(info.has_property("keepalive").to_string()),
+    );
+    scalar(
+        "run at load",
+        Some(plist.run_at_load.unwrap_or(false).to_string()),
+        Some(info.has_property("runatload").to_string()),
+    );
+    scalar(
+        "start interval",
+        plist.start_interval.map(|seconds| seconds.to_string()),
+        info.run_interval.as_ref().and_then(|interval| {
+            interval.split_whitespace().next().map(|seconds| seconds.to_string())
+        }),
+    );
+
+    let empty = BTreeMap::new();
+    let disk_env = plist.environment_variables.as_ref().unwrap_or(&empty);
+    let running_env: BTreeMap<&String, &String> = info
+        .environment
+        .iter()
+        .filter(|(key, _)| !IMPLICIT_ENVIRONMENT.contains(&key.as_str()))
+        .collect();
+    let keys: BTreeSet<&String> = disk_env.keys().chain(running_env.keys().copied()).collect();
+    for key in keys {
+        let on_disk = disk_env.get(key).cloned();
+        let running = running_env.get(key).map(|value| value.to_string());
+        scalar(&format!("env {}", key), on_disk, running);
+    }
+
+    differences
+}
+
+fn quote(arguments: &[String]) -> String {
+    arguments
+        .iter()
+        .map(|argument| match argument.contains(char::is_whitespace) || argument.is_empty() {
+            true => format!("{:?}", argument),
+            false => argument.clone(),
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SONOMA_GUI_SERVICE: &str =
+        include_str!("../../tests/fixtures/launchctl/sonoma-gui-service.txt");
+    const VENTURA_SYSTEM_SERVICE: &str =
+        include_str!("../../tests/fixtures/launchctl/ventura-system-service.txt");
+
+    /// The plist launchd is running in the Sonoma fixture.
+    fn sonoma_plist() -> Plist {
+        Plist {
+            label: Some("com.example.agent".to_string()),
+            program_arguments: Some(
+                [
+                    "/usr/local/bin/example",
+                    "--config",
+                    "/Users/tester/.example.conf",
+                    "--level=debug",
+                ]
+                .map(String::from)
+                .to_vec(),
+            ),
+            working_directory: Some("/Users/tester".to_string()),
+            standard_out_path: Some("/tmp/example.out".to_string()),
+            standard_error_path: Some("/tmp/example.err".to_string()),
+            environment_variables: Some(BTreeMap::from([(
+                "EXAMPLE_MODE".to_string(),
+                "production".to_string(),
+            )])),
+            keep_alive: Some(KeepAlive::Always(true)),
+            run_at_load: Some(true),
+            ..Default::default()
+        }
+    }
+
+    fn differences(plist: &Plist, fixture: &str) -> Vec<(String, Option<String>, Option<String>)> {
+        let info = Parser::parse_service_info(fixture).unwrap();
+        compare(plist, &info)
+            .into_iter()
+            .map(|difference| (difference.field, difference.on_disk, difference.running))
+            .collect()
+    }
+
+    fn row(
+        field: &str,
+        on_disk: Option<&str>,
+        running: Option<&str>,
+    ) -> (String, Option<String>, Option<String>) {
+        (field.to_string(), on_disk.map(String::from), running.map(String::from))
+    }
+
+    #[test]
+    fn matching_plist_has_no_differences() {
+        assert_eq!(differences(&sonoma_plist(), SONOMA_GUI_SERVICE), []);
+
+        // `Program` alongside the arguments is what launchd reports as the program anyway
+        let plist = Plist { program: Some("/usr/local/bin/example".to_string()), ..sonoma_plist() };
+        assert_eq!(differences(&plist, SONOMA_GUI_SERVICE), []);
+    }
+
+    #[test]
+    fn changed_keys_show_both_values() {
+        let mut plist = sonoma_plist();
+        plist.program_arguments.as_mut().unwrap()[3] = "--level=info".to_string();
+        plist.working_directory = Some("/tmp".to_string());
+        plist.run_at_load = Some(false);
+        plist
+            .environment_variables
+            .as_mut()
+            .unwrap()
+            .insert("EXAMPLE_MODE".to_string(), "staging".to_string());
+
+        assert_eq!(
+            differences(&plist, SONOMA_GUI_SERVICE),
+            [
+                row(
+                    "arguments",
+                    Some(
+                        "/usr/local/bin/example --config /Users/tester/.example.conf --level=info"
+                    ),
+                    Some(
+                        "/usr/local/bin/example --config /Users/tester/.example.conf --level=debug"
+                    ),
+                ),
+                row("working directory", Some("/tmp"), Some("/Users/tester")),
+                row("run at load", Some("false"), Some("true")),
+                row("env EXAMPLE_MODE", Some("staging"), Some("production")),
+            ]
+        );
+
+        let plist = Plist {
+            program: Some("/usr/local/sbin/backup".to_string()),
+            program_arguments: Some(
+                ["/usr/local/sbin/backup", "--target = /Volumes/Backup"].map(String::from).to_vec(),
+            ),
+            start_interval: Some(1800),
+            ..Default::default()
+        };
+        assert_eq!(
+            differences(&plist, VENTURA_SYSTEM_SERVICE),
+            [row("start interval", Some("1800"), Some("3600"))]
+        );
+    }
+
+    #[test]
+    fn missing_keys_show_one_side() {
+        let mut plist = sonoma_plist();
+        plist.standard_error_path = None;
+        plist.keep_alive = None;
+        plist.environment_variables =
+            Some(BTreeMap::from([("EXAMPLE_DEBUG".to_string(), "1".to_string())]));
+        plist.start_interval = Some(300);
+
+        assert_eq!(
+            differences(&plist, SONOMA_GUI_SERVICE),
+            [
+                row("stderr path", None, Some("/tmp/example.err")),
+                row("keep alive", Some("false"), Some("true")),
+                row("start interval", Some("300"), None),
+                row("env EXAMPLE_DEBUG", Some("1"), None),
+                row("env EXAMPLE_MODE", None, Some("production")),
+            ]
+        );
+    }
+}
@@ -0,0 +1,155 @@
+use crate::angel::Angel;
+use crate::cli::EditArgs;
+use crate::error::{Result, UserError};
+use crate::launchctl;
+use crate::output::{stderr, stdout};
+use crate::parser::Parser;
+use crate::prompt;
+use crate::types::{Daemon, Domain, Plist};
+use std::fs::{DirBuilder, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::os::unix::fs::{DirBuilderExt, OpenOptionsExt};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub fn run(angel: &Angel, args: &EditArgs) -> Result<()> {
+    let daemon =
+        angel.daemons.get_match(&args.name, args.exact, angel.domain(args.domain).as_ref())?;
+    let source_path = daemon.source_path.as_ref().ok_or_else(|| {
+        UserError::InvalidArgument(format!("{} has no plist file on disk", daemon.name))
+    })?;
+    // edit the file a symlink points at, not the link itself
+    let real_path = source_path.canonicalize()?;
+
+    let is_system = daemon.domain == Domain::System || angel.paths.is_system_path(&real_path);
+    if is_system && !angel.is_root() {
+        return Err(UserError::RequiresRoot.into());
+    }
+
+    let original = std::fs::read(&real_path)?;
+    let Some(edited) = edit_until_valid(&real_path, &original)? else {
+        stdout::writeln("No changes.");
+        return Ok(());
+    };
+    // write in place so the file keeps its owner and mode
+    std::fs::write(&real_path, &edited)?;
+    stdout::success(&format!("Saved {}", real_path.display()));
+
+    let loaded = Parser::parse_print_service(daemon)?.is_some();
+    if loaded && (args.reload || confirm_reload(daemon)?) {
+        reload(daemon)?;
+    }
+    Ok(())
+}
+
+/// Edit a scratch copy until it parses, so the real file never holds invalid content.
+/// Returns `None` when nothing changed.
+fn edit_until_valid(real_path: &Path, original: &[u8]) -> Result<Option<Vec<u8>>> {
+    let file_name = real_path.file_name().and_then(|n| n.to_str()).unwrap_or("service.plist");
+    let scratch_dir = create_scratch_dir()?;
+    let scratch = scratch_dir.join(file_name);
+    let result =
+        write_scratch(&scratch, original).and_then(|()| edit_loop(&scratch, real_path, original));
+
+    let cleanup = match std::fs::remove_file(&scratch) {
+        Err(e) if e.kind() != ErrorKind::NotFound => Err(e),
+        _ => std::fs::remove_dir(&scratch_dir),
+    };
+    if let Err(e) = cleanup {
+        stderr::warn(&format!("could not remove {}: {}", scratch_dir.display(), e));
+    }
+    result
+}
+
+/// A fresh directory only we can enter. `mkdir` never follows a symlink planted at the name,
+/// so an existing entry just means trying another name.
+fn create_scratch_dir() -> Result<PathBuf> {
+    let nanos = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.subsec_nanos());
+    for attempt in 0..100u32 {
+        let name = format!("angel-edit-{}-{:08x}", std::process::id(), nanos.wrapping_add(attempt));
+        let dir = std::env::temp_dir().join(name);
+        match DirBuilder::new().mode(0o700).create(&dir) {
+            Ok(()) => return Ok(dir),
+            Err(e) if e.kind() == ErrorKind::AlreadyExists => continue,
+            Err(e) => return Err(e.into()),
+        }
+    }
+    Err(std::io::Error::new(ErrorKind::AlreadyExists, "could not create a scratch directory")
+        .into())
+}
+
+fn write_scratch(scratch: &Path, contents: &[u8]) -> Result<()> {
+    let mut file = OpenOptions::new().write(true).create_new(true).mode(0o600).open(scratch)?;
+    file.write_all(contents)?;
+    Ok(())
+}
+
+fn edit_loop(scratch: &Path, real_path: &Path, original: &[u8]) -> Result<Option<Vec<u8>>> {
+    loop {
+        open_editor(scratch)?;
+        let edited = std::fs::read(scratch)?;
+        match plist::from_bytes::<Plist>(&edited) {
+            Ok(_) if edited == original => return Ok(None),
+            Ok(_) => return Ok(Some(edited)),
+            Err(e) => {
+                stdout::error(&format!("Invalid plist: {}", e));
+                if !confirm_edit_again()? {
+                    return Err(UserError::InvalidArgument(format!(
+                        "discarded invalid edit of {}",
+                        real_path.display()
+                    ))
+                    .into());
+                }
+            }
+        }
+    }
+}
+
+/// Run `$VISUAL` or `$EDITOR` (falling back to `vi`) through the shell, so values like
+/// `code --wait` work.
+fn open_editor(path: &Path) -> Result<()> {
+    let editor = std::env::var("VISUAL")
+        .or_else(|_| std::env::var("EDITOR"))
+        .ok()
+        .filter(|editor| !editor.trim().is_empty())
+        .unwrap_or_else(|| "vi".to_string());
+    let status = Command::new("sh")
+        .arg("-c")
+        .arg(format!("{} \"$1\"", editor))
+        .arg("sh")
+        .arg(path)
+        .status()?;
+    match status.success() {
+        true => Ok(()),
+        false => {
+            Err(std::io::Error::other(format!("editor `{}` exited with {}", editor, status)).into())
+        }
+    }
+}
+
+fn reload(daemon: &Daemon) -> Result<()> {
+    let result = launchctl::bootout(daemon)?;
+    if !result.success() {
+        stdout::error(&format!("failed to bootout {}: {}", daemon.name, result.stderr));
+        return Ok(());
+    }
+    let result = launchctl::bootstrap(daemon)?;
+    match result.success() {
+        true => stdout::success(&format!("reloaded {}", daemon.name)),
+        false => stdout::error(&format!("failed to bootstrap {}: {}", daemon.name, result.stderr)),
+    }
+    Ok(())
+}
+
+fn confirm_reload(daemon: &Daemon) -> Result<bool> {
+    prompt::confirm(&format!("{} is loaded. Bootout and bootstrap it now?", daemon.name), true)
+}
+
+/// Only asked interactively: answering yes automatically would reopen the editor forever.
+fn confirm_edit_again() -> Result<bool> {
+    if !prompt::is_interactive() {
+        return Ok(false);
+    }
+    prompt::confirm("Edit again? Answering no discards your changes", false)
+}
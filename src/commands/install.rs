@@ -1,26 +1,89 @@
 use crate::angel::Angel;
 use crate::cli::InstallArgs;
-use crate::error::{AngelError, Result, UserError};
+use crate::error::{AngelError, Result, SystemError, UserError};
 use crate::launchctl;
 use crate::output::stdout;
+use crate::prompt;
+use crate::schedule;
 use crate::types::{Daemon, Domain, ForWhom, Plist};
 use clap::ValueEnum;
 use nix::fcntl::{AT_FDCWD, AtFlags};
 use nix::sys::stat::Mode;
 use nix::unistd::{Gid, Uid, fchownat};
+use serde::{Deserialize, Serialize};
 use std::fs;
 use std::os::unix::fs::PermissionsExt;
 use std::path::{Path, PathBuf};
 
-#[derive(Clone, ValueEnum, PartialEq)]
+#[derive(Debug, Clone, ValueEnum, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum InstallStrategy {
     Copy,
     Symlink,
     Move,
 }
 
+/// How a plist got to where launchd reads it from.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InstallRecord {
+    /// The path given to `install`
+    pub source: PathBuf,
+    /// Where `install` put the plist, i.e. the service's `source_path`
+    pub target: PathBuf,
+    pub strategy: InstallStrategy,
+    /// e.g. `gui/501`
+    pub domain: String,
+    /// RFC 3339
+    pub installed_at: String,
+}
+
+/// Every install angel still knows how to undo, kept in `~/.config/angel/installed.yaml`.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct InstallRecords {
+    installs: Vec<InstallRecord>,
+}
+
+impl InstallRecords {
+    pub fn load(angel: &Angel) -> Result<Self> {
+        let Some(path) = angel.paths.install_records() else { return Ok(Self::default()) };
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = std::fs::read_to_string(&path)?;
+        serde_yaml::from_str(&content).map_err(|e| {
+            SystemError::Config(anyhow::anyhow!("Failed to parse {}: {}", path.display(), e)).into()
+        })
+    }
+
+    pub fn save(&self, angel: &Angel) -> Result<()> {
+        let Some(path) = angel.paths.install_records() else { return Ok(()) };
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let content =
+            serde_yaml::to_string(self).map_err(|e| SystemError::Serialize(e.to_string()))?;
+        std::fs::write(&path, content)?;
+        Ok(())
+    }
+
+    pub fn get(&self, target: &Path) -> Option<&InstallRecord> {
+        self.installs.iter().find(|record| record.target == target)
+    }
+
+    /// Add `record`, replacing whatever was installed at the same target before.
+    pub fn insert(&mut self, record: InstallRecord) {
+        self.remove(&record.target);
+        self.installs.push(record);
+    }
+
+    pub fn remove(&mut self, target: &Path) -> Option<InstallRecord> {
+        let index = self.installs.iter().position(|record| record.target == target)?;
+        Some(self.installs.remove(index))
+    }
+}
+
 pub fn run(angel: &Angel, args: &InstallArgs) -> Result<()> {
-    let source_path = PathBuf::from(args.path.as_str());
+    let source_path = std::path::absolute(args.path.as_str())?;
     stdout::writeln(&format!("source_path: {}", source_path.display()));
     let bytes = std::fs::read(&source_path)?;
 
@@ -31,17 +94,17 @@ pub fn run(angel: &Angel, args: &InstallArgs) -> Result<()> {
         .ok_or_else(|| UserError::InvalidArgument("No label found in plist".to_string()))?;
 
     // ask user which domain
-    let selected_domain = get_domain_selection(angel, &plist_data)?;
+    let selected_domain = get_domain_selection(angel, args, &plist_data)?;
 
     // copy/symlink/move
-    let target_path = make_target_path(&selected_domain, &service_name)?;
+    let target_path = make_target_path(angel, &selected_domain, &service_name)?;
     install_file(&args.strategy, &source_path, &target_path)?;
 
     // set permissions for system domains
     set_permissions(&selected_domain, &args.strategy, &source_path, &target_path)?;
 
     // kill running service if it is running
-    kill_running_service(angel, &service_name)?;
+    kill_running_service(angel, &service_name, &selected_domain)?;
 
     let daemon = Daemon::from_plist(
         plist_data,
@@ -52,23 +115,52 @@ pub fn run(angel: &Angel, args: &InstallArgs) -> Result<()> {
     );
 
     let result = launchctl::bootstrap(&daemon)?;
-    match result.success() {
-        true => stdout::success(&format!("installed {}", daemon.name)),
-        false => stdout::error(&format!("failed to install {}: {}", daemon.name, result.stderr)),
+    if !result.success() {
+        undo_install_file(&args.strategy, &source_path, &target_path)?;
+        return Err(UserError::ActionFailed(format!(
+            "failed to install {}: {}",
+            daemon.name,
+            result.stderr.trim()
+        ))
+        .into());
+    }
+    record_install(angel, args, &source_path, &target_path, &daemon.domain)?;
+    stdout::success(&format!("installed {}", daemon.name));
+    if let Some(summary) = daemon.plist.as_ref().and_then(schedule::summary) {
+        stdout::hint(&format!("scheduled {}", summary));
     }
     Ok(())
 }
 
-fn kill_running_service(angel: &Angel, service_name: &str) -> Result<()> {
-    let daemon = match angel.daemons.get_match(service_name, true) {
+/// Only called once the service is loaded, so a failed install leaves no record behind.
+fn record_install(
+    angel: &Angel,
+    args: &InstallArgs,
+    source_path: &Path,
+    target_path: &Path,
+    domain: &Domain,
+) -> Result<()> {
+    let mut records = InstallRecords::load(angel)?;
+    records.insert(InstallRecord {
+        source: source_path.to_path_buf(),
+        target: target_path.to_path_buf(),
+        strategy: args.strategy.clone(),
+        domain: domain.to_string(),
+        installed_at: jiff::Timestamp::now().to_string(),
+    });
+    records.save(angel)
+}
+
+fn kill_running_service(angel: &Angel, service_name: &str, domain: &Domain) -> Result<()> {
+    let daemon = match angel.daemons.get_match(service_name, true, Some(domain)) {
         Ok(daemon) => match daemon.pid {
             Some(_) => daemon,
             None => return Ok(()), // not running. proceed.
         },
         Err(AngelError::User(UserError::DaemonNotFound(_))) => return Ok(()), // not found. proceed.
-        Err(e) => return Err(e.into()),
+        Err(e) => return Err(e),
     };
-    confirm_kill_running_service(&daemon)?;
+    confirm_kill_running_service(daemon)?;
     launchctl::disable(daemon)?; // disable before bootout to prevent restart when keepAlive = true
     launchctl::bootout(daemon)?;
     launchctl::enable(daemon)?;
@@ -76,21 +168,19 @@ fn kill_running_service(angel: &Angel, service_name: &str) -> Result<()> {
 }
 
 fn confirm_kill_running_service(daemon: &Daemon) -> Result<()> {
-    dialoguer::Confirm::new()
-        .with_prompt(format!(
-            "A service with the name {} is already running. Run bootout?",
+    prompt::confirm(
+        &format!("A service with the name {} is already running. Run bootout?", daemon.name),
+        false,
+    )
+    .unwrap_or(false)
+    .then_some(())
+    .ok_or_else(|| {
+        UserError::InvalidArgument(format!(
+            "A service with the name {} is already running. Bootstrap will fail.",
             daemon.name
         ))
-        .interact()
-        .unwrap_or(false)
-        .then_some(())
-        .ok_or_else(|| {
-            UserError::InvalidArgument(format!(
-                "A service with the name {} is already running. Bootstrap will fail.",
-                daemon.name
-            ))
-            .into()
-        })
+        .into()
+    })
 }
 
 fn install_file(strategy: &InstallStrategy, source_path: &Path, target_path: &Path) -> Result<()> {
@@ -103,19 +193,34 @@ fn install_file(strategy: &InstallStrategy, source_path: &Path, target_path: &Pa
     Ok(())
 }
 
-fn confirm_overwrite(target_path: &Path) -> Result<()> {
+/// Put things back after launchd refused the plist: without a record, `uninstall` wouldn't
+/// know a moved file is the only copy.
+fn undo_install_file(
+    strategy: &InstallStrategy,
+    source_path: &Path,
+    target_path: &Path,
+) -> Result<()> {
+    match strategy {
+        InstallStrategy::Move => std::fs::rename(target_path, source_path)?,
+        InstallStrategy::Symlink | InstallStrategy::Copy => std::fs::remove_file(target_path)?,
+    }
+    Ok(())
+}
+
+/// Without a terminal, only `--yes` replaces an existing file.
+pub fn confirm_overwrite(target_path: &Path) -> Result<()> {
     if !target_path.exists() {
         return Ok(());
     }
-    let choice = dialoguer::Confirm::new()
-        .with_prompt(format!("A file already exists at {}. Overwrite it?", target_path.display()))
-        .default(true)
-        .interact()
-        .unwrap_or(false);
+    let choice = prompt::confirm(
+        &format!("A file already exists at {}. Overwrite it?", target_path.display()),
+        false,
+    )
+    .unwrap_or(false);
 
     if !choice {
         return Err(UserError::InvalidArgument(format!(
-            "A file already exists at {}",
+            "A file already exists at {} (pass --yes to overwrite it)",
             target_path.display()
         ))
         .into());
@@ -124,16 +229,21 @@ fn confirm_overwrite(target_path: &Path) -> Result<()> {
     Ok(())
 }
 
-fn get_domain_selection(angel: &Angel, plist_data: &Plist) -> Result<Domain> {
-    let domains =
-        [Domain::Gui(angel.uid.as_raw()), Domain::System, Domain::User(angel.uid.as_raw())];
-    let domain_selection_index = dialoguer::Select::new()
-        .with_prompt("In which domain should the service be installed?")
-        .items(&domains)
-        .default(0)
-        .interact()?;
-
-    let selected_domain = domains[domain_selection_index].clone();
+fn get_domain_selection(angel: &Angel, args: &InstallArgs, plist_data: &Plist) -> Result<Domain> {
+    let selected_domain = match args.domain {
+        Some(kind) => kind.domain(angel.uid.as_raw()),
+        None => {
+            let domains =
+                [Domain::Gui(angel.uid.as_raw()), Domain::System, Domain::User(angel.uid.as_raw())];
+            let index = prompt::select(
+                "In which domain should the service be installed?",
+                &domains,
+                0,
+                "pass --domain",
+            )?;
+            domains[index].clone()
+        }
+    };
     let plist_domain = Domain::from_plist(plist_data, angel.uid.as_raw(), selected_domain.clone());
     match plist_domain == selected_domain {
         false => {
@@ -157,10 +267,10 @@ fn get_domain_selection(angel: &Angel, plist_data: &Plist) -> Result<Domain> {
     Ok(selected_domain)
 }
 
-fn make_target_path(domain: &Domain, service_name: &str) -> Result<PathBuf> {
+fn make_target_path(angel: &Angel, domain: &Domain, service_name: &str) -> Result<PathBuf> {
     let target_dir = match domain {
-        Domain::System => PathBuf::from("/Library/LaunchDaemons"),
-        _ => dirs::home_dir().map(|home| home.join("Library/LaunchAgents")).ok_or_else(|| {
+        Domain::System => angel.paths.launch_daemons(),
+        _ => angel.paths.user_launch_agents().ok_or_else(|| {
             UserError::InvalidArgument("Could not determine user home directory".to_string())
         })?,
     };
@@ -176,8 +286,8 @@ fn make_target_path(domain: &Domain, service_name: &str) -> Result<PathBuf> {
 fn set_permissions(
     selected_domain: &Domain,
     strategy: &InstallStrategy,
-    _source_path: &PathBuf,
-    target_path: &PathBuf,
+    _source_path: &Path,
+    target_path: &Path,
 ) -> Result<()> {
     if *selected_domain == Domain::System && *strategy == InstallStrategy::Symlink {
         set_system_permissions(target_path)?;
@@ -201,7 +311,7 @@ fn set_system_permissions(path: &Path) -> Result<()> {
 
     // set permissions on symlink not target
     let mut perms = fs::symlink_metadata(path)?.permissions();
-    perms.set_mode(mode.bits() as u32);
+    perms.set_mode(mode.bits());
     fs::set_permissions(path, perms)?;
     Ok(())
 }
@@ -0,0 +1,511 @@
+use crate::angel::Angel;
+use crate::cli::LintArgs;
+use crate::daemon::get_plist_dirs;
+use crate::error::{Result, UserError};
+use crate::output::stdout;
+use crate::styles::styles;
+use crate::types::{ForWhom, KeepAlive, Plist};
+use crossterm::style::Color;
+use nix::unistd::{AccessFlags, access};
+use std::os::unix::fs::{MetadataExt, PermissionsExt};
+use std::path::{Path, PathBuf};
+
+const SESSION_TYPES: [&str; 5] = ["Aqua", "Background", "LoginWindow", "StandardIO", "System"];
+
+#[derive(Clone, Copy, PartialEq)]
+enum Severity {
+    Error,
+    Warning,
+}
+
+struct Finding {
+    severity: Severity,
+    line: Option<usize>,
+    message: String,
+}
+
+/// Findings for one file, plus its source text so findings can point at a line.
+struct Lint<'a> {
+    content: &'a str,
+    findings: Vec<Finding>,
+}
+
+impl Lint<'_> {
+    fn error(&mut self, key: Option<&str>, message: String) {
+        self.push(Severity::Error, key, message);
+    }
+
+    fn warn(&mut self, key: Option<&str>, message: String) {
+        self.push(Severity::Warning, key, message);
+    }
+
+    fn push(&mut self, severity: Severity, key: Option<&str>, message: String) {
+        let line = key.and_then(|key| key_line(self.content, key));
+        self.findings.push(Finding { severity, line, message });
+    }
+}
+
+pub fn run(angel: &Angel, args: &LintArgs) -> Result<()> {
+    let paths = resolve_paths(angel, args)?;
+
+    let mut failed = 0;
+    for path in &paths {
+        let findings = lint_file(path, is_system_path(angel, path));
+        if findings.iter().any(|f| f.severity == Severity::Error) {
+            failed += 1;
+        }
+        if !findings.is_empty() || paths.len() == 1 {
+            print_findings(path, &findings);
+        }
+    }
+
+    match failed {
+        0 => {
+            stdout::success(&format!("{} plist(s) checked", paths.len()));
+            Ok(())
+        }
+        n => Err(UserError::LintFailed(n).into()),
+    }
+}
+
+/// A path on disk, a service name, or every non-Apple plist angel scans.
+fn resolve_paths(angel: &Angel, args: &LintArgs) -> Result<Vec<PathBuf>> {
+    let Some(target) = &args.target else {
+        let mut paths: Vec<PathBuf> =
+            get_plist_dirs(&angel.config, &angel.paths, angel.uid.as_raw())
+                .into_iter()
+                .filter(|dir| dir.for_use_by != ForWhom::Apple)
+                .flat_map(|dir| {
+                    let pattern = format!("{}/*.plist", dir.path.display());
+                    glob::glob(&pattern).into_iter().flatten().flatten()
+                })
+                .collect();
+        paths.sort();
+        paths.dedup();
+        return Ok(paths);
+    };
+
+    let path = PathBuf::from(target);
+    if path.is_file() {
+        return Ok(vec![path]);
+    }
+    let daemon = angel.daemons.get_match(target, args.exact, angel.domain(args.domain).as_ref())?;
+    match &daemon.source_path {
+        Some(path) => Ok(vec![path.clone()]),
+        None => {
+            Err(UserError::InvalidArgument(format!("{} has no plist file on disk", daemon.name))
+                .into())
+        }
+    }
+}
+
+fn print_findings(path: &Path, findings: &[Finding]) {
+    stdout::writeln(styles::prefix(Color::Blue, &path.display().to_string()));
+    if findings.is_empty() {
+        stdout::writeln(format!("  {}", styles::command("no problems found")));
+    }
+    for finding in findings {
+        let severity = match finding.severity {
+            Severity::Error => styles::prefix(Color::Red, "error"),
+            Severity::Warning => styles::prefix(Color::Yellow, "warning"),
+        };
+        let line = finding.line.map(|l| format!("line {}: ", l)).unwrap_or_default();
+        stdout::writeln(format!("  {}{}: {}", line, severity, finding.message));
+    }
+}
+
+fn lint_file(path: &Path, system: bool) -> Vec<Finding> {
+    let bytes = match std::fs::read(path) {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            let message = format!("could not read file: {}", e);
+            return vec![Finding { severity: Severity::Error, line: None, message }];
+        }
+    };
+    let content = String::from_utf8_lossy(&bytes);
+    let mut lint = Lint { content: &content, findings: Vec::new() };
+
+    match plist::from_bytes::<Plist>(&bytes) {
+        Ok(plist) => check_plist(&mut lint, path, &plist),
+        Err(e) => {
+            let line = error_offset(&e)
+                .map(|offset| line_of(&content, offset))
+                .or_else(|| failing_key(&bytes).and_then(|key| key_line(&content, &key)));
+            lint.findings.push(Finding {
+                severity: Severity::Error,
+                line,
+                message: format!("could not parse plist: {}", describe(&e)),
+            });
+        }
+    }
+    if system {
+        check_ownership(&mut lint, path);
+    }
+    lint.findings
+}
+
+fn check_plist(lint: &mut Lint, path: &Path, plist: &Plist) {
+    let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or_default();
+    match &plist.label {
+        None => lint.error(None, "missing Label".to_string()),
+        Some(label) if label != stem => lint.warn(
+            Some("Label"),
+            format!("Label `{}` does not match the file name `{}.plist`", label, stem),
+        ),
+        Some(_) => {}
+    }
+
+    let program = plist.program.as_ref().map(|p| ("Program", p)).or_else(|| {
+        plist.program_arguments.as_ref().and_then(|a| a.first()).map(|p| ("ProgramArguments", p))
+    });
+    match program {
+        None => lint.error(None, "missing Program and ProgramArguments".to_string()),
+        Some((key, program)) => check_program(lint, key, program),
+    }
+
+    if let Some(dir) = &plist.working_directory
+        && !Path::new(dir).is_dir()
+    {
+        lint.error(Some("WorkingDirectory"), format!("WorkingDirectory {} does not exist", dir));
+    }
+
+    for (key, log) in [
+        ("StandardOutPath", &plist.standard_out_path),
+        ("StandardErrorPath", &plist.standard_error_path),
+    ] {
+        if let Some(log) = log {
+            check_log_path(lint, key, log);
+        }
+    }
+
+    let keeps_alive = match &plist.keep_alive {
+        Some(KeepAlive::Always(always)) => *always,
+        Some(KeepAlive::Conditions(_)) => true,
+        None => false,
+    };
+    if keeps_alive && plist.start_interval.is_some() {
+        lint.warn(
+            Some("StartInterval"),
+            "StartInterval has no effect on a service that is kept alive".to_string(),
+        );
+    }
+
+    for session_type in plist.limit_load_to_session_type.iter().flat_map(|s| s.as_slice()) {
+        if !SESSION_TYPES.contains(&session_type.as_str()) {
+            lint.error(
+                Some("LimitLoadToSessionType"),
+                format!("unknown LimitLoadToSessionType `{}`", session_type),
+            );
+        }
+    }
+}
+
+fn check_program(lint: &mut Lint, key: &str, program: &str) {
+    let path = Path::new(program);
+    if path.is_relative() {
+        lint.warn(Some(key), format!("program path `{}` is relative", program));
+        return;
+    }
+    match std::fs::metadata(path) {
+        Err(_) => lint.error(Some(key), format!("program {} does not exist", program)),
+        Ok(meta) if !meta.is_file() => {
+            lint.error(Some(key), format!("program {} is not a file", program))
+        }
+        Ok(meta) if meta.permissions().mode() & 0o111 == 0 => {
+            lint.error(Some(key), format!("program {} is not executable", program))
+        }
+        Ok(_) => {}
+    }
+}
+
+fn check_log_path(lint: &mut Lint, key: &str, log: &str) {
+    let dir = match Path::new(log).parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => return,
+    };
+    match () {
+        _ if !dir.is_dir() => {
+            lint.error(Some(key), format!("log directory {} does not exist", dir.display()))
+        }
+        _ if access(dir, AccessFlags::W_OK).is_err() => {
+            lint.warn(Some(key), format!("log directory {} is not writable", dir.display()))
+        }
+        _ => {}
+    }
+}
+
+/// launchd refuses plists in system locations unless they are owned by root and not
+/// writable by group or others.
+fn check_ownership(lint: &mut Lint, path: &Path) {
+    let Ok(meta) = std::fs::metadata(path) else { return };
+    if meta.uid() != 0 {
+        lint.error(None, format!("owned by uid {}, must be owned by root", meta.uid()));
+    }
+    if meta.mode() & 0o022 != 0 {
+        lint.error(None, format!("mode {:o} is group or world writable", meta.mode() & 0o777));
+    }
+}
+
+fn is_system_path(angel: &Angel, path: &Path) -> bool {
+    if angel.paths.is_system_path(path) {
+        return true;
+    }
+    let parent = path.parent();
+    get_plist_dirs(&angel.config, &angel.paths, angel.uid.as_raw())
+        .iter()
+        .any(|dir| Some(dir.path.as_path()) == parent && dir.domain == crate::types::Domain::System)
+}
+
+/// The byte offset plist reports in its error messages, e.g. `InvalidXmlSyntax (offset 85)`.
+fn error_offset(error: &plist::Error) -> Option<usize> {
+    let message = error.to_string();
+    let start = message.find("(offset ")? + "(offset ".len();
+    let end = message[start..].find(')')? + start;
+    message[start..end].parse().ok()
+}
+
+/// plist renders serde errors through `Debug`, nested once per layer. Unwrap them back to
+/// the message serde produced.
+fn describe(error: &plist::Error) -> String {
+    let mut message = error.to_string();
+    while let Some(inner) = message.strip_prefix("Serde(\"").and_then(|m| m.strip_suffix("\")")) {
+        message = inner.replace("\\\"", "\"").replace("\\\\", "\\");
+    }
+    message
+}
+
+/// Type errors surface after the XML has been read, so they carry no offset. Find the key
+/// whose value fails to deserialize on its own instead.
+fn failing_key(bytes: &[u8]) -> Option<String> {
+    let dict = plist::from_bytes::<plist::Dictionary>(bytes).ok()?;
+    dict.into_iter().find_map(|(key, value)| {
+        let single = plist::Dictionary::from_iter([(key.clone(), value)]);
+        plist::from_value::<Plist>(&plist::Value::Dictionary(single)).is_err().then_some(key)
+    })
+}
+
+fn line_of(content: &str, offset: usize) -> usize {
+    let offset = offset.min(content.len());
+    content.as_bytes()[..offset].iter().filter(|&&b| b == b'\n').count() + 1
+}
+
+fn key_line(content: &str, key: &str) -> Option<usize> {
+    content.find(&format!("<key>{}</key>", key)).map(|offset| line_of(content, offset))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LABEL: &str = "<key>Label</key><string>com.example.lint</string>";
+    const PROGRAM: &str = "<key>Program</key><string>/bin/sh</string>";
+
+    /// One rule: a plist body it accepts, one it flags, and the finding it flags it with.
+    /// `{dir}` in a body stands for a scratch directory holding the plist and a non-executable
+    /// `script` file.
+    struct Case {
+        rule: &'static str,
+        good: &'static str,
+        bad: &'static str,
+        severity: Severity,
+        finding: &'static str,
+    }
+
+    const CASES: &[Case] = &[
+        Case {
+            rule: "Label is required",
+            good: "<key>Label</key><string>com.example.lint</string>",
+            bad: "",
+            severity: Severity::Error,
+            finding: "missing Label",
+        },
+        Case {
+            rule: "Label matches the file name",
+            good: "<key>Label</key><string>com.example.lint</string>",
+            bad: "<key>Label</key><string>com.example.other</string>",
+            severity: Severity::Warning,
+            finding: "Label `com.example.other` does not match the file name `com.example.lint.plist`",
+        },
+        Case {
+            rule: "a program is required",
+            good: "<key>Program</key><string>/bin/sh</string>",
+            bad: "",
+            severity: Severity::Error,
+            finding: "missing Program and ProgramArguments",
+        },
+        Case {
+            rule: "the program path is absolute",
+            good: "<key>ProgramArguments</key><array><string>/bin/sh</string></array>",
+            bad: "<key>ProgramArguments</key><array><string>sh</string></array>",
+            severity: Severity::Warning,
+            finding: "program path `sh` is relative",
+        },
+        Case {
+            rule: "the program exists",
+            good: "<key>Program</key><string>/bin/sh</string>",
+            bad: "<key>Program</key><string>{dir}/missing</string>",
+            severity: Severity::Error,
+            finding: "program {dir}/missing does not exist",
+        },
+        Case {
+            rule: "the program is a file",
+            good: "<key>Program</key><string>/bin/sh</string>",
+            bad: "<key>Program</key><string>{dir}</string>",
+            severity: Severity::Error,
+            finding: "program {dir} is not a file",
+        },
+        Case {
+            rule: "the program is executable",
+            good: "<key>Program</key><string>/bin/sh</string>",
+            bad: "<key>Program</key><string>{dir}/script</string>",
+            severity: Severity::Error,
+            finding: "program {dir}/script is not executable",
+        },
+        Case {
+            rule: "the working directory exists",
+            good: "<key>WorkingDirectory</key><string>{dir}</string>",
+            bad: "<key>WorkingDirectory</key><string>{dir}/missing</string>",
+            severity: Severity::Error,
+            finding: "WorkingDirectory {dir}/missing does not exist",
+        },
+        Case {
+            rule: "the log directory exists",
+            good: "<key>StandardErrorPath</key><string>{dir}/err.log</string>",
+            bad: "<key>StandardErrorPath</key><string>{dir}/missing/err.log</string>",
+            severity: Severity::Error,
+            finding: "log directory {dir}/missing does not exist",
+        },
+        Case {
+            rule: "StartInterval is pointless with KeepAlive",
+            good: "<key>StartInterval</key><integer>60</integer>",
+            bad: "<key>StartInterval</key><integer>60</integer><key>KeepAlive</key><true/>",
+            severity: Severity::Warning,
+            finding: "StartInterval has no effect on a service that is kept alive",
+        },
+        Case {
+            rule: "session types are known",
+            good: "<key>LimitLoadToSessionType</key><array><string>Aqua</string></array>",
+            bad: "<key>LimitLoadToSessionType</key><string>Desktop</string>",
+            severity: Severity::Error,
+            finding: "unknown LimitLoadToSessionType `Desktop`",
+        },
+        Case {
+            rule: "values have the right type",
+            good: "<key>RunAtLoad</key><true/>",
+            bad: "<key>RunAtLoad</key><string>yes</string>",
+            severity: Severity::Error,
+            finding: "could not parse plist",
+        },
+    ];
+
+    /// A scratch directory for one case, removed when dropped.
+    struct Scratch(PathBuf);
+
+    impl Scratch {
+        fn new(name: &str) -> Self {
+            let dir =
+                std::env::temp_dir().join(format!("angel-lint-{}-{}", std::process::id(), name));
+            let _ = std::fs::remove_dir_all(&dir);
+            std::fs::create_dir_all(&dir).unwrap();
+            std::fs::write(dir.join("script"), "#!/bin/sh\n").unwrap();
+            std::fs::set_permissions(dir.join("script"), std::fs::Permissions::from_mode(0o644))
+                .unwrap();
+            Self(dir)
+        }
+
+        /// Write `com.example.lint.plist` with `body` inside its top-level dict.
+        fn write(&self, body: &str) -> PathBuf {
+            let path = self.0.join("com.example.lint.plist");
+            let xml = format!(
+                "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<plist version=\"1.0\">\n<dict>\n{}\n</dict>\n</plist>\n",
+                body.replace("><key>", ">\n<key>")
+            );
+            std::fs::write(&path, xml).unwrap();
+            path
+        }
+
+        fn expand(&self, text: &str) -> String {
+            text.replace("{dir}", &self.0.display().to_string())
+        }
+    }
+
+    impl Drop for Scratch {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.0);
+        }
+    }
+
+    /// Label and Program, unless `provides` sets them itself, followed by `body`.
+    fn plist_body(provides: &str, body: &str) -> String {
+        let mut keys = String::new();
+        if !provides.contains("<key>Label</key>") {
+            keys.push_str(LABEL);
+        }
+        if !provides.contains("<key>Program") {
+            keys.push_str(PROGRAM);
+        }
+        keys + body
+    }
+
+    fn messages(findings: &[Finding]) -> Vec<&str> {
+        findings.iter().map(|f| f.message.as_str()).collect()
+    }
+
+    #[test]
+    fn each_rule_accepts_good_and_flags_bad_plists() {
+        for (i, case) in CASES.iter().enumerate() {
+            let scratch = Scratch::new(&i.to_string());
+            // A required key's bad body is empty, so leave out what the good body provides
+            let good = scratch.expand(&plist_body(case.good, case.good));
+            let bad = scratch.expand(&plist_body(case.good, case.bad));
+
+            let findings = lint_file(&scratch.write(&good), false);
+            assert!(
+                findings.is_empty(),
+                "{}: good plist flagged: {:?}",
+                case.rule,
+                messages(&findings)
+            );
+
+            let findings = lint_file(&scratch.write(&bad), false);
+            let expected = scratch.expand(case.finding);
+            let finding = findings.iter().find(|f| f.message.starts_with(&expected));
+            let finding = finding.unwrap_or_else(|| {
+                panic!("{}: expected `{}`, got {:?}", case.rule, expected, messages(&findings))
+            });
+            assert!(finding.severity == case.severity, "{}: wrong severity", case.rule);
+        }
+    }
+
+    #[test]
+    fn findings_point_at_the_key_line() {
+        let scratch = Scratch::new("lines");
+        let path = scratch.write(&plist_body(
+            "",
+            "<key>LimitLoadToSessionType</key><string>Desktop</string><key>RunAtLoad</key><true/>",
+        ));
+        let findings = lint_file(&path, false);
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].line, Some(6));
+
+        let path = scratch.write(&plist_body("", "<key>RunAtLoad</key><string>yes</string>"));
+        assert_eq!(lint_file(&path, false)[0].line, Some(6));
+    }
+
+    #[test]
+    fn system_plists_must_not_be_writable_by_others() {
+        let scratch = Scratch::new("ownership");
+        let path = scratch.write(&plist_body("", ""));
+        let writable = |findings: &[Finding]| {
+            findings.iter().any(|f| f.message.ends_with("is group or world writable"))
+        };
+
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o644)).unwrap();
+        assert!(!writable(&lint_file(&path, true)));
+
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o664)).unwrap();
+        assert!(writable(&lint_file(&path, true)));
+        // Only system locations are held to launchd's ownership rules
+        assert!(lint_file(&path, false).is_empty());
+    }
+}
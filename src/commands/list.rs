@@ -1,11 +1,15 @@
 use crate::angel::Angel;
 use crate::cli::ListArgs;
-use crate::styles::styles;
+use crate::daemon::DaemonRegistry;
 use crate::error::Result;
 use crate::output;
 use crate::output::stdout;
-use crate::types::ForWhom;
+use crate::styles::styles;
+use crate::types::{Daemon, Domain, ForWhom};
+use crate::watch::{self, JobState, Transition};
 use clap::ValueEnum;
+use comfy_table::Table;
+use std::collections::HashMap;
 
 #[derive(Clone, ValueEnum)]
 pub enum SortBy {
@@ -15,33 +19,122 @@ pub enum SortBy {
 }
 
 pub fn run(angel: &Angel, args: &ListArgs) -> Result<()> {
+    if let Some(interval) = args.watch {
+        return watch(angel, args, interval);
+    }
+
+    let matching_daemons = matching(angel, &angel.daemons, args, &HashMap::new())?;
+
+    if output::is_structured() {
+        let records: Vec<_> = matching_daemons.iter().map(|daemon| daemon.record(None)).collect();
+        return output::emit(&records);
+    }
+
+    stdout::writeln(&table(&matching_daemons, &HashMap::new()));
+    print_diagnostics(angel);
+    Ok(())
+}
+
+/// The services `angel list` shows for `args`, sorted. Services in `changes` are kept even if
+/// the toggles would hide them.
+pub fn matching<'a>(
+    angel: &Angel,
+    daemons: &'a DaemonRegistry,
+    args: &ListArgs,
+    changes: &HashMap<(Domain, String), Transition>,
+) -> Result<Vec<&'a Daemon>> {
     let query = args.pattern.as_deref().unwrap_or("");
-    let mut matching_daemons = angel.daemons.get_matches(query, args.exact)?;
+    let mut matching_daemons =
+        daemons.get_matches(query, args.exact, angel.domain(args.domain).as_ref())?;
     sort_daemons(args.sort_by.clone(), &mut matching_daemons);
+    // keep a job that just exited on screen for the tick that shows it exiting
+    matching_daemons.retain(|daemon| {
+        !is_hidden(daemon, args)
+            || changes.contains_key(&(daemon.domain.clone(), daemon.name.clone()))
+    });
+    Ok(matching_daemons)
+}
 
+fn table(daemons: &[&Daemon], changes: &HashMap<(Domain, String), Transition>) -> Table {
     let mut table = styles::create_table();
-    table.set_header(vec!["EC", "PID", "Domain", "Name", "Source"]);
-
-    for daemon in &matching_daemons {
-        match (daemon.for_use_by == ForWhom::Apple && !args.show_apple)
-            || (daemon.source_path.is_none() && !args.show_dynamic)
-            || (daemon.pid.is_none() && !args.show_idle)
-        {
-            true => continue,
-            false => {}
-        }
+    table.set_header(vec!["EC", "PID", "Disabled", "Domain", "Name", "Source"]);
 
-        table.add_row(vec![
+    for daemon in daemons {
+        let cells = vec![
             daemon.last_exit_code.clone().unwrap_or("-".to_string()),
             daemon.pid.map_or("-".to_string(), |p| p.to_string()),
-            (&daemon.domain).to_string(),
+            match daemon.disabled {
+                Some(true) => "yes".to_string(),
+                _ => "-".to_string(),
+            },
+            daemon.domain.to_string(),
             daemon.name.clone(),
             styles::display_path(daemon, output::is_verbose()),
-        ]);
+        ];
+        let transition = changes.get(&(daemon.domain.clone(), daemon.name.clone())).copied();
+        table.add_row(watch::row(cells, transition));
     }
+    table
+}
 
-    stdout::writeln(&table);
-    Ok(())
+fn watch(angel: &Angel, args: &ListArgs, interval: f64) -> Result<()> {
+    let uid = angel.uid.as_raw();
+    let mut daemons = angel.daemons.clone();
+    let mut previous: Option<HashMap<(Domain, String), JobState>> = None;
+
+    watch::run("angel list", interval, || {
+        if previous.is_some() {
+            daemons.refresh(uid);
+        }
+        let current: HashMap<_, _> = daemons
+            .get_matches("", false, None)?
+            .into_iter()
+            .map(|daemon| {
+                let key = (daemon.domain.clone(), daemon.name.clone());
+                (key, JobState::new(daemon.pid, daemon.last_exit_code.clone()))
+            })
+            .collect();
+        let changes: HashMap<_, _> = match &previous {
+            Some(previous) => current
+                .iter()
+                .filter_map(|(key, state)| {
+                    let transition = previous.get(key)?.transition(state)?;
+                    Some((key.clone(), transition))
+                })
+                .collect(),
+            None => HashMap::new(),
+        };
+        previous = Some(current);
+
+        let matching_daemons = matching(angel, &daemons, args, &changes)?;
+        Ok(table(&matching_daemons, &changes).to_string())
+    })
+}
+
+/// Plists that were skipped or shadowed while scanning, so a missing service can be explained.
+fn print_diagnostics(angel: &Angel) {
+    let diagnostics = angel.daemons.diagnostics();
+    match () {
+        _ if diagnostics.is_empty() => {}
+        _ if output::is_verbose() => {
+            for diagnostic in diagnostics {
+                stdout::warn(&diagnostic.to_string());
+            }
+        }
+        _ => stdout::hint(&format!(
+            "{} plist(s) had problems while scanning, run with --verbose for details",
+            diagnostics.len()
+        )),
+    }
+}
+
+fn is_hidden(daemon: &Daemon, args: &ListArgs) -> bool {
+    (daemon.for_use_by == ForWhom::Apple && !args.show_apple)
+        || (daemon.source_path.is_none() && !args.show_dynamic)
+        || match args.only_disabled {
+            true => daemon.disabled != Some(true),
+            false => daemon.pid.is_none() && !args.show_idle,
+        }
 }
 
 fn sort_daemons(sort_by: SortBy, daemons: &mut Vec<&crate::types::Daemon>) {
@@ -0,0 +1,391 @@
+use crate::angel::Angel;
+use crate::cli::LogsArgs;
+use crate::config::expand_tilde;
+use crate::error::{Result, UserError};
+use crate::output::stdout;
+use crate::parser::Parser;
+use crate::styles::styles;
+use crate::types::Daemon;
+use crossterm::style::Color;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::os::unix::fs::MetadataExt;
+use std::path::PathBuf;
+use std::thread;
+use std::time::Duration;
+
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+#[derive(Clone, Copy, PartialEq)]
+enum Stream {
+    Out,
+    Err,
+}
+
+impl Stream {
+    fn tag(self) -> String {
+        match self {
+            Stream::Out => styles::prefix(Color::Green, "out"),
+            Stream::Err => styles::prefix(Color::Red, "err"),
+        }
+    }
+}
+
+/// A log file being followed. Tracks the inode and offset so truncation and rotation are
+/// noticed on the next poll.
+struct LogFile {
+    path: PathBuf,
+    stream: Stream,
+    file: Option<File>,
+    inode: u64,
+    offset: u64,
+    partial: String,
+}
+
+pub fn run(angel: &Angel, args: &LogsArgs) -> Result<()> {
+    let daemon =
+        angel.daemons.get_match(&args.name, args.exact, angel.domain(args.domain).as_ref())?;
+    let mut logs = resolve_logs(daemon, args.stdout, args.stderr)?;
+    let interleave = args.interleave && logs.len() > 1;
+    let headers = !interleave && logs.len() > 1;
+
+    for log in &mut logs {
+        let lines = log.open_at_tail(args.lines)?;
+        if headers {
+            stdout::writeln(styles::command(&format!("==> {} <==", log.path.display())));
+        }
+        for line in lines {
+            print_line(log.stream, &line, interleave);
+        }
+    }
+
+    if !args.follow {
+        return Ok(());
+    }
+
+    let mut last_printed: Option<usize> = None;
+    loop {
+        for (i, log) in logs.iter_mut().enumerate() {
+            let lines = log.poll()?;
+            if lines.is_empty() {
+                continue;
+            }
+            if headers && last_printed != Some(i) {
+                stdout::writeln(styles::command(&format!("==> {} <==", log.path.display())));
+            }
+            last_printed = Some(i);
+            for line in lines {
+                print_line(log.stream, &line, interleave);
+            }
+        }
+        thread::sleep(POLL_INTERVAL);
+    }
+}
+
+/// The last `n` lines of each of a service's log files, with a `==> path <==` header before
+/// each file when there are several.
+pub fn tail(daemon: &Daemon, n: usize) -> Result<Vec<String>> {
+    let mut logs = resolve_logs(daemon, false, false)?;
+    let headers = logs.len() > 1;
+    let mut lines = Vec::new();
+    for log in &mut logs {
+        if headers {
+            lines.push(format!("==> {} <==", log.path.display()));
+        }
+        lines.extend(log.open_at_tail(n)?);
+    }
+    Ok(lines)
+}
+
+fn print_line(stream: Stream, line: &str, interleave: bool) {
+    match interleave {
+        true => stdout::writelogln(stream.tag(), line),
+        false => stdout::writeln(line),
+    }
+}
+
+/// Pick the log files to show from the plist, falling back to what launchd reports for
+/// services angel has no plist for.
+fn resolve_logs(daemon: &Daemon, stdout: bool, stderr: bool) -> Result<Vec<LogFile>> {
+    let (mut out, mut err) = match &daemon.plist {
+        Some(plist) => (plist.standard_out_path.clone(), plist.standard_error_path.clone()),
+        None => (None, None),
+    };
+    if out.is_none()
+        && err.is_none()
+        && let Some(info) = Parser::parse_print_service(daemon)?
+    {
+        out = info.stdout_path;
+        err = info.stderr_path;
+    }
+
+    let mut streams = Vec::new();
+    if !stderr {
+        streams.extend(out.map(|path| (Stream::Out, expand_tilde(&path))));
+    }
+    if !stdout {
+        streams.extend(err.map(|path| (Stream::Err, expand_tilde(&path))));
+    }
+    // stdout and stderr commonly point at the same file
+    streams.dedup_by(|a, b| a.1 == b.1);
+
+    if streams.is_empty() {
+        let which = match (stdout, stderr) {
+            (true, _) => "StandardOutPath",
+            (_, true) => "StandardErrorPath",
+            _ => "StandardOutPath or StandardErrorPath",
+        };
+        return Err(UserError::InvalidArgument(format!("{} has no {}", daemon.name, which)).into());
+    }
+
+    Ok(streams
+        .into_iter()
+        .map(|(stream, path)| LogFile {
+            path,
+            stream,
+            file: None,
+            inode: 0,
+            offset: 0,
+            partial: String::new(),
+        })
+        .collect())
+}
+
+impl LogFile {
+    /// Open the file and return its last `n` lines, leaving the offset at the end.
+    /// A file that doesn't exist yet is picked up by `poll` once it appears.
+    fn open_at_tail(&mut self, n: usize) -> Result<Vec<String>> {
+        let mut file = match File::open(&self.path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+        let lines = tail_lines(&mut file, n)?;
+        self.inode = file.metadata()?.ino();
+        self.offset = file.stream_position()?;
+        self.file = Some(file);
+        Ok(lines)
+    }
+
+    /// Return complete lines written since the last poll.
+    fn poll(&mut self) -> Result<Vec<String>> {
+        let mut lines = Vec::new();
+        let current = std::fs::metadata(&self.path).ok();
+
+        // Rotated: drain what's left of the old file, then start over on the new one
+        let rotated = current.as_ref().is_some_and(|meta| meta.ino() != self.inode);
+        if rotated || self.file.is_none() {
+            if let Some(file) = self.file.as_mut() {
+                lines.extend(read_new_lines(file, &mut self.partial)?);
+            }
+            self.partial.clear();
+            self.file = None;
+            if current.is_some() {
+                match File::open(&self.path) {
+                    Ok(file) => {
+                        self.inode = file.metadata()?.ino();
+                        self.offset = 0;
+                        self.file = Some(file);
+                    }
+                    // Rotated again since the metadata check: pick the new file up next poll
+                    Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                    Err(e) => return Err(e.into()),
+                }
+            }
+        }
+
+        let Some(file) = self.file.as_mut() else { return Ok(lines) };
+
+        // Truncated in place: read again from the start
+        if file.metadata()?.len() < self.offset {
+            file.seek(SeekFrom::Start(0))?;
+            self.partial.clear();
+        }
+
+        lines.extend(read_new_lines(file, &mut self.partial)?);
+        self.offset = file.stream_position()?;
+        Ok(lines)
+    }
+}
+
+fn read_new_lines(file: &mut File, partial: &mut String) -> Result<Vec<String>> {
+    let mut buf = Vec::new();
+    file.read_to_end(&mut buf)?;
+    partial.push_str(&String::from_utf8_lossy(&buf));
+
+    let mut lines = Vec::new();
+    while let Some(pos) = partial.find('\n') {
+        lines.push(partial[..pos].to_string());
+        partial.drain(..=pos);
+    }
+    Ok(lines)
+}
+
+/// Read the last `n` lines by scanning backwards in blocks, so large logs aren't read whole.
+fn tail_lines(file: &mut File, n: usize) -> Result<Vec<String>> {
+    const BLOCK: u64 = 8192;
+    let len = file.seek(SeekFrom::End(0))?;
+    let mut start = len;
+    let mut buf: Vec<u8> = Vec::new();
+
+    while start > 0 && buf.iter().filter(|&&b| b == b'\n').count() <= n {
+        let read = BLOCK.min(start);
+        start -= read;
+        let mut block = vec![0; read as usize];
+        file.seek(SeekFrom::Start(start))?;
+        file.read_exact(&mut block)?;
+        block.extend(buf);
+        buf = block;
+    }
+    file.seek(SeekFrom::Start(len))?;
+
+    let text = String::from_utf8_lossy(&buf);
+    let lines: Vec<&str> = text.strip_suffix('\n').unwrap_or(&text).split('\n').collect();
+    if n == 0 || text.is_empty() {
+        return Ok(Vec::new());
+    }
+    Ok(lines[lines.len().saturating_sub(n)..].iter().map(|l| l.to_string()).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    /// A scratch log file under the temp dir, removed with its directory when dropped.
+    struct Scratch {
+        dir: PathBuf,
+        path: PathBuf,
+    }
+
+    impl Scratch {
+        fn new(name: &str) -> Self {
+            let dir =
+                std::env::temp_dir().join(format!("angel-logs-{}-{}", std::process::id(), name));
+            let _ = std::fs::remove_dir_all(&dir);
+            std::fs::create_dir_all(&dir).unwrap();
+            let path = dir.join("out.log");
+            Self { dir, path }
+        }
+
+        fn write(&self, contents: &str) {
+            std::fs::write(&self.path, contents).unwrap();
+        }
+
+        fn append(&self, contents: &str) {
+            let mut file = std::fs::OpenOptions::new().append(true).open(&self.path).unwrap();
+            file.write_all(contents.as_bytes()).unwrap();
+        }
+
+        fn log(&self) -> LogFile {
+            LogFile {
+                path: self.path.clone(),
+                stream: Stream::Out,
+                file: None,
+                inode: 0,
+                offset: 0,
+                partial: String::new(),
+            }
+        }
+
+        fn tail(&self, n: usize) -> Vec<String> {
+            tail_lines(&mut File::open(&self.path).unwrap(), n).unwrap()
+        }
+    }
+
+    impl Drop for Scratch {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.dir);
+        }
+    }
+
+    #[test]
+    fn tail_lines_returns_the_last_lines() {
+        let scratch = Scratch::new("tail");
+        scratch.write("one\ntwo\nthree\n");
+        assert_eq!(scratch.tail(2), ["two", "three"]);
+        assert_eq!(scratch.tail(0), Vec::<String>::new());
+    }
+
+    #[test]
+    fn tail_lines_of_a_short_file() {
+        let scratch = Scratch::new("short");
+        scratch.write("one\ntwo\n");
+        assert_eq!(scratch.tail(10), ["one", "two"]);
+
+        scratch.write("");
+        assert_eq!(scratch.tail(10), Vec::<String>::new());
+    }
+
+    #[test]
+    fn tail_lines_without_a_trailing_newline() {
+        let scratch = Scratch::new("unterminated");
+        scratch.write("one\ntwo\nthree");
+        assert_eq!(scratch.tail(2), ["two", "three"]);
+        assert_eq!(scratch.tail(5), ["one", "two", "three"]);
+    }
+
+    #[test]
+    fn tail_lines_across_blocks() {
+        let scratch = Scratch::new("blocks");
+        let lines: Vec<String> = (0..5000).map(|i| format!("line {}", i)).collect();
+        scratch.write(&format!("{}\n", lines.join("\n")));
+        assert_eq!(scratch.tail(3000), lines[2000..]);
+    }
+
+    #[test]
+    fn poll_returns_appended_lines() {
+        let scratch = Scratch::new("append");
+        scratch.write("old\n");
+        let mut log = scratch.log();
+        assert_eq!(log.open_at_tail(10).unwrap(), ["old"]);
+        assert!(log.poll().unwrap().is_empty());
+
+        scratch.append("new\npart");
+        assert_eq!(log.poll().unwrap(), ["new"]);
+        scratch.append("ial\n");
+        assert_eq!(log.poll().unwrap(), ["partial"]);
+    }
+
+    #[test]
+    fn poll_picks_up_a_file_that_appears_later() {
+        let scratch = Scratch::new("missing");
+        let mut log = scratch.log();
+        assert!(log.open_at_tail(10).unwrap().is_empty());
+        assert!(log.poll().unwrap().is_empty());
+
+        scratch.write("first\n");
+        assert_eq!(log.poll().unwrap(), ["first"]);
+    }
+
+    #[test]
+    fn poll_after_truncate_reads_from_the_start() {
+        let scratch = Scratch::new("truncate");
+        scratch.write("a long first line\nand a second\n");
+        let mut log = scratch.log();
+        log.open_at_tail(10).unwrap();
+
+        scratch.write("short\n");
+        assert_eq!(log.poll().unwrap(), ["short"]);
+        scratch.append("more\n");
+        assert_eq!(log.poll().unwrap(), ["more"]);
+    }
+
+    #[test]
+    fn poll_after_rename_drains_the_old_file_then_follows_the_new_one() {
+        let scratch = Scratch::new("rotate");
+        scratch.write("before\n");
+        let mut log = scratch.log();
+        log.open_at_tail(10).unwrap();
+
+        scratch.append("last old line\n");
+        std::fs::rename(&scratch.path, scratch.dir.join("out.log.1")).unwrap();
+        // Nothing at the path yet: keep reading the rotated file
+        assert_eq!(log.poll().unwrap(), ["last old line"]);
+
+        scratch.write("first new line\n");
+        assert_eq!(log.poll().unwrap(), ["first new line"]);
+        scratch.append("second new line\n");
+        assert_eq!(log.poll().unwrap(), ["second new line"]);
+    }
+}
@@ -1,14 +1,62 @@
+use crate::angel::Angel;
+use crate::cli::Commands;
+use crate::error::Result;
+
+pub mod apply;
+pub mod batch;
 pub mod bootout;
 pub mod bootstrap;
+pub mod completions;
+pub mod diff;
 pub mod disable;
+pub mod edit;
 pub mod enable;
 pub mod install;
+pub mod lint;
 pub mod list;
+pub mod logs;
+pub mod new;
+pub mod overrides;
 pub mod print;
 pub mod restart;
+pub mod schedule;
 pub mod show;
 pub mod start;
 pub mod status;
 pub mod stop;
+pub mod tui;
 pub mod uninstall;
 pub mod version;
+
+/// Run one parsed subcommand.
+pub fn run(angel: &Angel, command: Commands) -> Result<()> {
+    match command {
+        Commands::Start(args) => start::run(angel, &args),
+        Commands::Stop(args) => stop::run(angel, &args),
+        Commands::Restart(args) => restart::run(angel, &args),
+        Commands::Status(args) => status::run(angel, &args),
+        Commands::List(args) => list::run(angel, &args),
+        Commands::Plist(args) => show::run(angel, &args),
+        Commands::New(args) => new::run(angel, &args),
+        Commands::Edit(args) => edit::run(angel, &args),
+        Commands::Install(args) => install::run(angel, &args),
+        Commands::Apply(args) => apply::run(angel, &args),
+        Commands::Uninstall(args) => uninstall::run(angel, &args),
+        Commands::Bootstrap(args) => bootstrap::run(angel, &args),
+        Commands::Bootout(args) => bootout::run(angel, &args),
+        Commands::Enable(args) => enable::run(angel, &args),
+        Commands::Disable(args) => disable::run(angel, &args),
+        Commands::Overrides(args) => overrides::run(angel, &args),
+        Commands::Print(args) => print::run(angel, &args),
+        Commands::Diff(args) => diff::run(angel, &args),
+        Commands::Schedule(args) => schedule::run(angel, &args),
+        Commands::Tui => tui::run(angel),
+        Commands::Logs(args) => logs::run(angel, &args),
+        Commands::Lint(args) => lint::run(angel, &args),
+        Commands::Completions(args) => completions::run(angel, &args),
+        Commands::Version => {
+            version::run();
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,163 @@
+use crate::angel::Angel;
+use crate::cli::NewArgs;
+use crate::commands::install::confirm_overwrite;
+use crate::error::{Result, UserError};
+use crate::launchctl;
+use crate::manifest::ServiceSpec;
+use crate::output::{stderr, stdout};
+use crate::prompt;
+use crate::schedule;
+use crate::types::{CalendarInterval, Daemon, Domain, DomainKind, ForWhom, Plist};
+use std::path::PathBuf;
+
+pub fn run(angel: &Angel, args: &NewArgs) -> Result<()> {
+    let domain_kind = match args.domain {
+        Some(kind) => kind,
+        None => prompt_domain()?,
+    };
+    let uid = angel.uid.as_raw();
+    let domain = domain_kind.domain(uid);
+    if !angel.daemons.get_matches(&args.label, true, Some(&domain))?.is_empty() {
+        stderr::warn(&format!("a service labelled {} already exists in {}", args.label, domain));
+    }
+    if args.bootstrap && domain_kind == DomainKind::System && !angel.is_root() {
+        return Err(UserError::RequiresRoot.into());
+    }
+
+    let plist = build_plist(args)?;
+    let target_path = make_target_path(angel, &domain, &args.label)?;
+    if let Some(parent) = target_path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    confirm_overwrite(&target_path)?;
+    plist::to_file_xml(&target_path, &plist)?;
+    stdout::success(&format!("created {}", target_path.display()));
+    if let Some(summary) = schedule::summary(&plist) {
+        stdout::hint(&format!("scheduled {}", summary));
+    }
+
+    if !args.bootstrap {
+        return Ok(());
+    }
+    let daemon = Daemon::from_plist(plist, Some(target_path), domain, ForWhom::Angel, uid);
+    let result = launchctl::bootstrap(&daemon)?;
+    match result.success() {
+        true => {
+            stdout::success(&format!("bootstrapped {}", daemon.name));
+            Ok(())
+        }
+        false => Err(UserError::ActionFailed(format!(
+            "failed to bootstrap {}: {}",
+            daemon.name,
+            result.stderr.trim()
+        ))
+        .into()),
+    }
+}
+
+/// Build the plist from flags, prompting for the program and the common switches when
+/// neither `--program` nor `--args` was given.
+fn build_plist(args: &NewArgs) -> Result<Plist> {
+    let interactive = args.program.is_none() && args.arguments.is_empty();
+
+    let (program, arguments) = match interactive {
+        true => prompt_command()?,
+        false => (args.program.clone(), args.arguments.clone()),
+    };
+    let run_at_load = match interactive && !args.run_at_load {
+        true => prompt::confirm("Start the service when it is loaded (RunAtLoad)?", false)?,
+        false => args.run_at_load,
+    };
+    let keep_alive = match interactive && !args.keep_alive {
+        true => prompt::confirm("Restart the service whenever it exits (KeepAlive)?", false)?,
+        false => args.keep_alive,
+    };
+    let (stdout_path, stderr_path) = match interactive {
+        true => (
+            match &args.stdout {
+                Some(path) => Some(path.clone()),
+                None => prompt_optional("StandardOutPath")?,
+            },
+            match &args.stderr {
+                Some(path) => Some(path.clone()),
+                None => prompt_optional("StandardErrorPath")?,
+            },
+        ),
+        false => (args.stdout.clone(), args.stderr.clone()),
+    };
+    let mut calendar = args.calendar.clone();
+    calendar.extend(args.cron.iter().flat_map(|cron| cron.0.iter().cloned()));
+    let unscheduled = args.interval.is_none() && calendar.is_empty();
+    if interactive && unscheduled && !keep_alive {
+        calendar = prompt_schedule()?;
+    }
+
+    ServiceSpec {
+        label: args.label.clone(),
+        domain: args.domain,
+        program,
+        args: arguments,
+        env: args.env.iter().cloned().collect(),
+        working_directory: args.workdir.clone(),
+        stdout: stdout_path,
+        stderr: stderr_path,
+        run_at_load,
+        keep_alive,
+        interval: args.interval,
+        calendar,
+        plist: Default::default(),
+    }
+    .to_plist()
+}
+
+fn prompt_domain() -> Result<DomainKind> {
+    let kinds = [DomainKind::Gui, DomainKind::User, DomainKind::System];
+    let index = prompt::select(
+        "In which domain should the service run?",
+        &["gui", "user", "system"],
+        0,
+        "pass --domain",
+    )?;
+    Ok(kinds[index])
+}
+
+fn prompt_command() -> Result<(Option<String>, Vec<String>)> {
+    let program = prompt::input("Program (absolute path)", false, "pass --program or --args")?;
+    let arguments = prompt::input("Arguments (space separated)", true, "pass --args")?;
+    Ok((Some(program), arguments.split_whitespace().map(String::from).collect()))
+}
+
+/// Ask for a cron expression until one parses or the answer is empty.
+fn prompt_schedule() -> Result<Vec<CalendarInterval>> {
+    loop {
+        let expr =
+            prompt::input("Schedule as cron, e.g. 30 2 * * 1-5 (leave empty to skip)", true, "")?;
+        if expr.trim().is_empty() {
+            return Ok(Vec::new());
+        }
+        match schedule::parse_cron(&expr) {
+            Ok(calendar) => return Ok(calendar),
+            Err(e) => stderr::warn(&e),
+        }
+    }
+}
+
+fn prompt_optional(key: &str) -> Result<Option<String>> {
+    let value = prompt::input(&format!("{} (leave empty to skip)", key), true, "")?;
+    Ok(Some(value).filter(|v| !v.is_empty()))
+}
+
+fn make_target_path(angel: &Angel, domain: &Domain, label: &str) -> Result<PathBuf> {
+    let dir = angel.paths.angel_dir(domain).ok_or_else(|| {
+        UserError::InvalidArgument("Could not determine user home directory".to_string())
+    })?;
+    Ok(dir.join(format!("{}.plist", label)))
+}
+
+/// Parse a `KEY=VALUE` pair for `--env`.
+pub fn parse_env(s: &str) -> std::result::Result<(String, String), String> {
+    match s.split_once('=') {
+        Some((key, value)) if !key.is_empty() => Ok((key.to_string(), value.to_string())),
+        _ => Err(format!("expected KEY=VALUE, got `{}`", s)),
+    }
+}
@@ -0,0 +1,163 @@
+use crate::angel::Angel;
+use crate::cli::{NameArgs, OverridesArgs, OverridesCommand, OverridesListArgs};
+use crate::error::{Result, UserError};
+use crate::output::{self, stderr, stdout};
+use crate::overrides::{self, OverrideDb};
+use crate::prompt;
+use crate::styles::styles;
+use crate::types::{Domain, DomainKind};
+use comfy_table::{Cell, Color};
+use serde::Serialize;
+use std::path::Path;
+
+/// One override, as emitted by `overrides list --format`.
+#[derive(Serialize)]
+struct OverrideRecord<'a> {
+    label: &'a str,
+    disabled: bool,
+    /// `system` or `uid <uid>`
+    scope: String,
+    path: &'a Path,
+}
+
+pub fn run(angel: &Angel, args: &OverridesArgs) -> Result<()> {
+    match &args.command {
+        OverridesCommand::List(args) => list(angel, args),
+        OverridesCommand::Clear(args) => clear(angel, args),
+    }
+}
+
+fn list(angel: &Angel, args: &OverridesListArgs) -> Result<()> {
+    let databases = load(angel)?;
+    let query = args.pattern.as_deref().unwrap_or("");
+    let matches = find_matches(angel, &databases, query, args.exact, args.domain);
+
+    if output::is_structured() {
+        let records: Vec<_> = matches
+            .iter()
+            .map(|&(index, label)| OverrideRecord {
+                label,
+                disabled: databases[index].overrides[label],
+                scope: databases[index].scope(),
+                path: &databases[index].path,
+            })
+            .collect();
+        return output::emit(&records);
+    }
+
+    if matches.is_empty() {
+        stdout::hint("No overrides match");
+        return Ok(());
+    }
+    let mut table = styles::create_table();
+    table.set_header(vec!["Name", "Override", "Scope", "Database"]);
+    for &(index, label) in &matches {
+        let db = &databases[index];
+        let state = match db.overrides[label] {
+            true => Cell::new("disabled").fg(Color::Red),
+            false => Cell::new("enabled").fg(Color::Green),
+        };
+        table.add_row(vec![
+            Cell::new(label),
+            state,
+            Cell::new(db.scope()),
+            Cell::new(db.path.display()),
+        ]);
+    }
+    stdout::writeln(&table);
+    Ok(())
+}
+
+fn clear(angel: &Angel, args: &NameArgs) -> Result<()> {
+    let mut databases = load(angel)?;
+    let matches = find_matches(angel, &databases, &args.name, args.exact, args.domain);
+    let (index, label) = match matches.len() {
+        0 => return Err(UserError::DaemonNotFound(args.name.clone()).into()),
+        1 => matches[0],
+        _ => {
+            let items: Vec<String> = matches
+                .iter()
+                .map(|&(index, label)| format!("{} ({})", label, databases[index].scope()))
+                .collect();
+            if !prompt::is_interactive() {
+                let query = args.name.clone();
+                return Err(UserError::AmbiguousMatch { query, candidates: items }.into());
+            }
+            let selection = prompt::select(
+                &format!("Multiple overrides found matching '{}'. Select one:", args.name),
+                &items,
+                0,
+                "pass --exact or --domain",
+            )?;
+            matches[selection]
+        }
+    };
+    let label = label.to_string();
+    let db = &mut databases[index];
+
+    let question = format!(
+        "Clear the override for `{}` (disabled = {}) in `{}`?",
+        label,
+        db.overrides[&label],
+        db.path.display()
+    );
+    if !prompt::confirm(&question, false)? {
+        stdout::writeln("Clear cancelled.");
+        return Ok(());
+    }
+    clear_override(db, &label)
+}
+
+/// Remove `label` from `db` and say where the backup went. Shared with `uninstall`.
+pub fn clear_override(db: &mut OverrideDb, label: &str) -> Result<()> {
+    match db.clear(label)? {
+        Some(backup) => {
+            stdout::success(&format!("Cleared override for {} in {}", label, db.path.display()));
+            stdout::hint(&format!("Previous database saved to {}", backup.display()));
+            stdout::hint(
+                "launchd rereads it at boot and overwrites it on the next enable or disable",
+            );
+        }
+        None => stdout::hint(&format!("{} has no override in {}", label, db.path.display())),
+    }
+    Ok(())
+}
+
+/// Every database, warning about any that couldn't be read. Fails only when none could.
+fn load(angel: &Angel) -> Result<Vec<OverrideDb>> {
+    let (databases, mut errors) = overrides::load_all(&angel.paths);
+    if databases.is_empty() && !errors.is_empty() {
+        return Err(errors.remove(0));
+    }
+    for e in errors {
+        stderr::warn(&format!("skipping an override database: {}", e));
+    }
+    Ok(databases)
+}
+
+/// Overrides whose label matches `query` the way service names do: a case-insensitive
+/// substring, the whole label with `exact`, or a `domain/label` target. Returns indexes into
+/// `databases` alongside each label.
+fn find_matches<'a>(
+    angel: &Angel,
+    databases: &'a [OverrideDb],
+    query: &str,
+    exact: bool,
+    kind: Option<DomainKind>,
+) -> Vec<(usize, &'a str)> {
+    let (domain, query, exact) = match Domain::parse_target(query) {
+        Some((domain, label)) => (Some(domain), label, true),
+        None => (angel.domain(kind), query, exact),
+    };
+    let needle = query.to_lowercase();
+    databases
+        .iter()
+        .enumerate()
+        .filter(|(_, db)| domain.as_ref().is_none_or(|domain| db.domains().contains(domain)))
+        .flat_map(|(index, db)| db.overrides.keys().map(move |label| (index, label.as_str())))
+        .filter(|(_, label)| match exact {
+            true => *label == query,
+            false => label.to_lowercase().contains(&needle),
+        })
+        .collect()
+}
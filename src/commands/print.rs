@@ -2,11 +2,17 @@ use crate::angel::Angel;
 use crate::cli::NameArgs;
 use crate::error::Result;
 use crate::launchctl;
-use crate::output::stdout;
+use crate::output::{self, stdout};
+use crate::parser::Parser;
 
 pub fn run(angel: &Angel, args: &NameArgs) -> Result<()> {
-    let daemon = angel.daemons.get_match(&args.name, args.exact)?;
+    let daemon =
+        angel.daemons.get_match(&args.name, args.exact, angel.domain(args.domain).as_ref())?;
     let result = launchctl::print(daemon)?;
+    if output::is_structured() {
+        let info = result.success().then(|| Parser::parse_service_info(&result.output)).flatten();
+        return output::emit(&daemon.record(info.as_ref()));
+    }
     stdout::write(&result.output);
     Ok(())
 }
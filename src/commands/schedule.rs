@@ -0,0 +1,92 @@
+use crate::angel::Angel;
+use crate::cli::ScheduleArgs;
+use crate::error::Result;
+use crate::output::{self, stdout};
+use crate::schedule::{self, Run};
+use crate::styles::styles;
+use crate::types::{Daemon, Domain, ForWhom};
+use jiff::Zoned;
+use serde::Serialize;
+
+/// One upcoming run in the timeline.
+#[derive(Serialize)]
+struct ScheduledRun<'a> {
+    /// RFC 3339, in local time
+    at: String,
+    /// Counted from now for StartInterval jobs, since launchd doesn't report when they last ran
+    estimated: bool,
+    name: &'a str,
+    domain: &'a Domain,
+    schedule: String,
+}
+
+pub fn run(angel: &Angel, args: &ScheduleArgs) -> Result<()> {
+    let query = args.pattern.as_deref().unwrap_or("");
+    let daemons =
+        angel.daemons.get_matches(query, args.exact, angel.domain(args.domain).as_ref())?;
+    let now = Zoned::now();
+
+    let mut timeline: Vec<(Run, &Daemon, String)> = daemons
+        .into_iter()
+        .filter(|daemon| args.show_apple || daemon.for_use_by != ForWhom::Apple)
+        .filter_map(|daemon| {
+            let plist = daemon.plist.as_ref()?;
+            let description = schedule::describe(plist)?;
+            let runs = schedule::next_runs(plist, &now, args.count);
+            Some(runs.into_iter().map(move |run| (run, daemon, description.clone())))
+        })
+        .flatten()
+        .collect();
+    timeline.sort_by(|(a, a_daemon, _), (b, b_daemon, _)| {
+        a.at.timestamp().cmp(&b.at.timestamp()).then_with(|| a_daemon.name.cmp(&b_daemon.name))
+    });
+
+    if output::is_structured() {
+        let records: Vec<_> = timeline
+            .iter()
+            .map(|(run, daemon, description)| ScheduledRun {
+                at: run.at.strftime("%Y-%m-%dT%H:%M:%S%:z").to_string(),
+                estimated: run.estimated,
+                name: &daemon.name,
+                domain: &daemon.domain,
+                schedule: description.clone(),
+            })
+            .collect();
+        return output::emit(&records);
+    }
+
+    if timeline.is_empty() {
+        stdout::hint("No scheduled services match");
+        return Ok(());
+    }
+    let mut table = styles::create_table();
+    table.set_header(vec!["When", "In", "Domain", "Name", "Schedule"]);
+    for (run, daemon, description) in &timeline {
+        table.add_row(vec![
+            run.display(),
+            until(&now, &run.at),
+            daemon.domain.to_string(),
+            daemon.name.clone(),
+            description.clone(),
+        ]);
+    }
+    stdout::writeln(&table);
+    if timeline.iter().any(|(run, _, _)| run.estimated) {
+        stdout::hint(
+            "~ StartInterval runs are counted from now; launchd doesn't say when they last ran",
+        );
+    }
+    Ok(())
+}
+
+/// Time from `now` to `at`, to the minute, e.g. `2d 3h` or `45m`.
+fn until(now: &Zoned, at: &Zoned) -> String {
+    let minutes = (at.timestamp().as_second() - now.timestamp().as_second()).max(0) / 60;
+    let (days, hours, minutes) = (minutes / 1440, minutes / 60 % 24, minutes % 60);
+    match (days, hours, minutes) {
+        (0, 0, 0) => "<1m".to_string(),
+        (0, 0, minutes) => format!("{}m", minutes),
+        (0, hours, minutes) => format!("{}h {}m", hours, minutes),
+        (days, hours, _) => format!("{}d {}h", days, hours),
+    }
+}
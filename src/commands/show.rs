@@ -1,13 +1,20 @@
 use crate::angel::Angel;
 use crate::cli::NameArgs;
 use crate::error::Result;
-use crate::output::stdout;
+use crate::output::{self, stdout};
+use crate::types::Plist;
 
 pub fn run(angel: &Angel, args: &NameArgs) -> Result<()> {
-    let daemon = angel.daemons.get_match(&args.name, args.exact)?;
+    let daemon =
+        angel.daemons.get_match(&args.name, args.exact, angel.domain(args.domain).as_ref())?;
     let path = daemon.source_path.as_ref().ok_or_else(|| {
         crate::error::SystemError::Launchctl("Daemon has no source path".to_string())
     })?;
+    if output::is_structured() {
+        // Re-read rather than using daemon.plist so the file on disk is what gets converted
+        let plist = plist::from_file::<_, Plist>(path)?;
+        return output::emit(&plist);
+    }
     let content = std::fs::read_to_string(path)?;
     stdout::write(&content);
     Ok(())
@@ -1,22 +1,13 @@
 use crate::angel::Angel;
 use crate::cli::StartArgs;
+use crate::commands::batch::{self, Action};
 use crate::error::Result;
 use crate::launchctl;
-use crate::output::stdout;
 
 pub fn run(angel: &Angel, args: &StartArgs) -> Result<()> {
-    let daemon = angel.daemons.get_match(&args.name, args.exact)?;
-    let result = match args.kill {
-        true => launchctl::kickstart_kill(daemon)?,
-        false => launchctl::kickstart(daemon)?,
-    };
-    stdout::write(&result.output);
-    match result.success() {
-        true => stdout::success(&format!("started {}", daemon.name)),
-        false => stdout::error(&format!(
-            "failed to start {}: {}",
-            daemon.name, result.stderr
-        )),
-    }
-    Ok(())
+    let action = Action { verb: "start", past: "started" };
+    batch::run(angel, &args.targets, action, |daemon| match args.kill {
+        true => launchctl::kickstart_kill(daemon),
+        false => launchctl::kickstart(daemon),
+    })
 }
@@ -1,24 +1,69 @@
 use crate::angel::Angel;
-use crate::cli::NameArgs;
+use crate::cli::StatusArgs;
 use crate::error::Result;
-use crate::launchctl;
-use crate::output::{is_verbose, stdout};
+use crate::output::{self, is_verbose, stdout};
+use crate::parser::Parser;
+use crate::schedule;
 use crate::styles::styles;
+use crate::types::{Daemon, ServiceInfo};
+use crate::watch::{self, JobState, Transition};
 use crossterm::style::Color;
+use jiff::Zoned;
 
-pub fn run(angel: &Angel, args: &NameArgs) -> Result<()> {
-    let daemon = angel.daemons.get_match(&args.name, args.exact)?;
-    let result = launchctl::print(daemon)?;
+pub fn run(angel: &Angel, args: &StatusArgs) -> Result<()> {
+    let target = &args.target;
+    let daemon = angel.daemons.get_match(
+        &target.name,
+        target.exact,
+        angel.domain(target.domain).as_ref(),
+    )?;
+    if let Some(interval) = args.watch {
+        return watch(daemon, interval);
+    }
+    let info = Parser::parse_print_service(daemon)?;
 
-    let status = extract_status(&result.output);
-    let color = if result.success() { None } else { Some(Color::Red) };
+    if output::is_structured() {
+        return output::emit(&daemon.record(info.as_ref()));
+    }
 
-    stdout::writeln(&styles::prefix(Color::Blue, &daemon.name));
-    stdout::writeln(&styles::format_status_dot(&status, color));
+    stdout::writeln(render(daemon, info.as_ref(), None));
+    Ok(())
+}
+
+fn render(daemon: &Daemon, info: Option<&ServiceInfo>, transition: Option<Transition>) -> String {
+    let status = info.map_or("unknown".to_string(), |info| info.state.to_string());
+    let color = if info.is_some() { None } else { Some(Color::Red) };
+    let status = match transition {
+        Some(transition) => {
+            format!("{}  {}", styles::format_status_dot(&status, color), transition.describe())
+        }
+        None => styles::format_status_dot(&status, color),
+    };
 
     let mut table = styles::create_table();
     table.add_row(vec!["Domain:".to_string(), daemon.domain_str()]);
     table.add_row(vec!["Source:".to_string(), styles::display_path(daemon, true)]);
+    // launchd enables anything without an override or `Disabled` key
+    let disabled = if daemon.disabled == Some(true) { "yes" } else { "no" };
+    table.add_row(vec!["Disabled:".to_string(), disabled.to_string()]);
+    if let Some(info) = info {
+        if let Some(pid) = info.pid {
+            table.add_row(watch::row(vec!["PID:".to_string(), pid.to_string()], transition));
+        }
+        if let Some(last_exit_code) = &info.last_exit_code {
+            let cells = vec!["Last exit:".to_string(), last_exit_code.clone()];
+            table.add_row(watch::row(cells, transition));
+        }
+    }
+
+    if let Some(plist) = &daemon.plist
+        && let Some(schedule) = schedule::describe(plist)
+    {
+        table.add_row(vec!["Schedule:".to_string(), schedule]);
+        if let Some(run) = schedule::next_runs(plist, &Zoned::now(), 1).first() {
+            table.add_row(vec!["Next run:".to_string(), run.display()]);
+        }
+    }
 
     if is_verbose() {
         // Add plist fields if available
@@ -32,18 +77,18 @@ pub fn run(angel: &Angel, args: &NameArgs) -> Result<()> {
         }
     }
 
-    stdout::writeln(&table);
-    Ok(())
+    format!("{}\n{}\n{}", styles::prefix(Color::Blue, &daemon.name), status, table)
 }
 
-fn extract_status(output: &str) -> String {
-    // Simple extraction - look for common status indicators
-    match () {
-        _ if output.contains("state = running") => "running".to_string(),
-        _ if output.contains("state = stopped") => "stopped".to_string(),
-        _ if output.contains("state = launched") => "launched".to_string(),
-        _ if output.contains("state = exited") => "exited".to_string(),
-        _ if output.contains("state = not running") => "not running".to_string(),
-        _ => "unknown".to_string(),
-    }
+fn watch(daemon: &Daemon, interval: f64) -> Result<()> {
+    let mut previous: Option<JobState> = None;
+    watch::run(&format!("angel status {}", daemon.name), interval, || {
+        let info = Parser::parse_print_service(daemon)?;
+        let current = info.as_ref().map_or(JobState::new(None, None), |info| {
+            JobState::new(info.pid, info.last_exit_code.clone())
+        });
+        let transition = previous.as_ref().and_then(|previous| previous.transition(&current));
+        previous = Some(current);
+        Ok(render(daemon, info.as_ref(), transition))
+    })
 }
@@ -0,0 +1,495 @@
+use crate::angel::Angel;
+use crate::cli::ListArgs;
+use crate::commands::batch::Action;
+use crate::commands::list::{self, SortBy};
+use crate::commands::logs;
+use crate::daemon::DaemonRegistry;
+use crate::error::{Result, UserError};
+use crate::launchctl::{self, LaunchctlResult};
+use crate::output;
+use crate::parser::Parser;
+use crate::prompt;
+use crate::types::{Daemon, Domain, DomainKind, ServiceInfo};
+use crossterm::cursor::{Hide, MoveTo, Show};
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use crossterm::style::{Color, Print, Stylize};
+use crossterm::terminal::{self, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen};
+use crossterm::{execute, queue};
+use std::collections::HashMap;
+use std::io::{IsTerminal, Stdout, Write};
+use std::time::{Duration, Instant};
+
+const REFRESH_INTERVAL: Duration = Duration::from_secs(2);
+
+const HELP: &str = "↑↓ move  / filter  tab domain  A apple  D dynamic  I idle  p pane  l logs  \
+                    s/x/r start/stop/restart  e/d enable/disable  q quit";
+
+/// What the right-hand pane shows for the selected service.
+#[derive(Clone, Copy, PartialEq)]
+enum Pane {
+    Plist,
+    Print,
+    Logs,
+}
+
+impl Pane {
+    fn next(self) -> Pane {
+        match self {
+            Pane::Plist => Pane::Print,
+            Pane::Print => Pane::Logs,
+            Pane::Logs => Pane::Plist,
+        }
+    }
+
+    fn title(self) -> &'static str {
+        match self {
+            Pane::Plist => "plist",
+            Pane::Print => "launchctl print",
+            Pane::Logs => "logs",
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Command {
+    Start,
+    Stop,
+    Restart,
+    Enable,
+    Disable,
+}
+
+impl Command {
+    fn action(self) -> Action {
+        match self {
+            Command::Start => Action { verb: "start", past: "started" },
+            Command::Stop => Action { verb: "stop", past: "stopped" },
+            Command::Restart => Action { verb: "restart", past: "restarted" },
+            Command::Enable => Action { verb: "enable", past: "enabled" },
+            Command::Disable => Action { verb: "disable", past: "disabled" },
+        }
+    }
+
+    /// Stopping or disabling the wrong service is the expensive mistake, so those ask first.
+    fn needs_confirmation(self) -> bool {
+        matches!(self, Command::Stop | Command::Disable)
+    }
+
+    fn run(self, daemon: &Daemon) -> Result<LaunchctlResult> {
+        match self {
+            Command::Start => launchctl::kickstart(daemon),
+            Command::Stop => launchctl::kill(daemon, "SIGTERM"),
+            Command::Restart => launchctl::kickstart_kill(daemon),
+            Command::Enable => launchctl::enable(daemon),
+            Command::Disable => launchctl::disable(daemon),
+        }
+    }
+}
+
+/// Restores the terminal however the browser exits.
+struct Screen;
+
+impl Screen {
+    fn enter() -> Result<Self> {
+        terminal::enable_raw_mode()?;
+        execute!(std::io::stdout(), EnterAlternateScreen, Hide)?;
+        Ok(Screen)
+    }
+}
+
+impl Drop for Screen {
+    fn drop(&mut self) {
+        let _ = execute!(std::io::stdout(), Show, LeaveAlternateScreen);
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+struct App<'a> {
+    angel: &'a Angel,
+    daemons: DaemonRegistry,
+    /// The filter and toggles, in the shape `angel list` takes them
+    args: ListArgs,
+    filtering: bool,
+    selected: Option<(Domain, String)>,
+    offset: usize,
+    pane: Pane,
+    scroll: usize,
+    /// Rendered pane for the selection, dropped whenever it may be stale
+    detail: Option<Vec<String>>,
+    pending: Option<Command>,
+    message: Option<(Color, String)>,
+}
+
+pub fn run(angel: &Angel) -> Result<()> {
+    if output::is_structured() || !prompt::is_interactive() || !std::io::stdout().is_terminal() {
+        return Err(UserError::InvalidArgument("angel tui needs a terminal".to_string()).into());
+    }
+
+    let mut app = App {
+        angel,
+        daemons: angel.daemons.clone(),
+        args: ListArgs {
+            pattern: None,
+            exact: false,
+            domain: None,
+            show_apple: false,
+            show_dynamic: false,
+            show_idle: true,
+            only_disabled: false,
+            sort_by: SortBy::Name,
+            watch: None,
+        },
+        filtering: false,
+        selected: None,
+        offset: 0,
+        pane: Pane::Plist,
+        scroll: 0,
+        detail: None,
+        pending: None,
+        message: None,
+    };
+
+    let _screen = Screen::enter()?;
+    let mut out = std::io::stdout();
+    let mut refreshed = Instant::now();
+    loop {
+        app.draw(&mut out)?;
+        let timeout = REFRESH_INTERVAL.saturating_sub(refreshed.elapsed());
+        if event::poll(timeout)?
+            && let Event::Key(key) = event::read()?
+            && key.kind == KeyEventKind::Press
+            && !app.handle(key)
+        {
+            return Ok(());
+        }
+        if refreshed.elapsed() >= REFRESH_INTERVAL {
+            app.refresh();
+            refreshed = Instant::now();
+        }
+    }
+}
+
+impl App<'_> {
+    fn visible(&self) -> Vec<&Daemon> {
+        list::matching(self.angel, &self.daemons, &self.args, &HashMap::new()).unwrap_or_default()
+    }
+
+    fn selected_index(&self, visible: &[&Daemon]) -> usize {
+        self.selected
+            .as_ref()
+            .and_then(|(domain, name)| {
+                visible.iter().position(|daemon| daemon.domain == *domain && daemon.name == *name)
+            })
+            .unwrap_or(0)
+    }
+
+    fn select(&mut self, daemon: Option<&Daemon>) {
+        let key = daemon.map(|daemon| (daemon.domain.clone(), daemon.name.clone()));
+        if key != self.selected {
+            self.selected = key;
+            self.scroll = 0;
+            self.detail = None;
+        }
+    }
+
+    fn move_selection(&mut self, delta: isize) {
+        let visible = self.visible();
+        let index = self.selected_index(&visible).saturating_add_signed(delta);
+        let daemon = visible.get(index.min(visible.len().saturating_sub(1))).copied().cloned();
+        self.select(daemon.as_ref());
+    }
+
+    fn selected_daemon(&self) -> Option<Daemon> {
+        let visible = self.visible();
+        visible.get(self.selected_index(&visible)).map(|daemon| (*daemon).clone())
+    }
+
+    fn refresh(&mut self) {
+        self.daemons.refresh(self.angel.uid.as_raw());
+        self.detail = None;
+    }
+
+    /// Returns false once the user asks to quit.
+    fn handle(&mut self, key: KeyEvent) -> bool {
+        if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+            return false;
+        }
+
+        if let Some(command) = self.pending.take() {
+            match key.code {
+                KeyCode::Char('y') => self.execute(command),
+                _ => self.message = Some((Color::DarkGrey, "cancelled".to_string())),
+            }
+            return true;
+        }
+
+        if self.filtering {
+            let pattern = self.args.pattern.get_or_insert_default();
+            match key.code {
+                KeyCode::Char(c) => pattern.push(c),
+                KeyCode::Backspace => {
+                    pattern.pop();
+                }
+                KeyCode::Enter => self.filtering = false,
+                KeyCode::Esc => {
+                    self.args.pattern = None;
+                    self.filtering = false;
+                }
+                _ => {}
+            }
+            self.move_selection(0);
+            return true;
+        }
+
+        let page = terminal::size().map_or(10, |(_, height)| (height as usize / 2).max(1));
+        match key.code {
+            KeyCode::Char('q') => return false,
+            KeyCode::Esc => self.args.pattern = None,
+            KeyCode::Up | KeyCode::Char('k') => self.move_selection(-1),
+            KeyCode::Down | KeyCode::Char('j') => self.move_selection(1),
+            KeyCode::PageUp => self.scroll = self.scroll.saturating_sub(page),
+            KeyCode::PageDown => self.scroll += page,
+            KeyCode::Char('/') => self.filtering = true,
+            KeyCode::Tab => {
+                self.args.domain = match self.args.domain {
+                    None => Some(DomainKind::Gui),
+                    Some(DomainKind::Gui) => Some(DomainKind::User),
+                    Some(DomainKind::User) => Some(DomainKind::System),
+                    Some(DomainKind::System) => None,
+                }
+            }
+            KeyCode::Char('A') => self.args.show_apple = !self.args.show_apple,
+            KeyCode::Char('D') => self.args.show_dynamic = !self.args.show_dynamic,
+            KeyCode::Char('I') => self.args.show_idle = !self.args.show_idle,
+            KeyCode::Char('p') => self.set_pane(self.pane.next()),
+            KeyCode::Char('l') => self.set_pane(Pane::Logs),
+            KeyCode::Char('s') => self.request(Command::Start),
+            KeyCode::Char('x') => self.request(Command::Stop),
+            KeyCode::Char('r') => self.request(Command::Restart),
+            KeyCode::Char('e') => self.request(Command::Enable),
+            KeyCode::Char('d') => self.request(Command::Disable),
+            _ => {}
+        }
+        self.move_selection(0);
+        true
+    }
+
+    fn set_pane(&mut self, pane: Pane) {
+        self.pane = pane;
+        self.scroll = 0;
+        self.detail = None;
+    }
+
+    fn request(&mut self, command: Command) {
+        let Some(daemon) = self.selected_daemon() else { return };
+        match command.needs_confirmation() {
+            true => {
+                self.pending = Some(command);
+                let prompt = format!("{} {}? (y/n)", command.action().verb, daemon.name);
+                self.message = Some((Color::Yellow, prompt));
+            }
+            false => self.execute(command),
+        }
+    }
+
+    fn execute(&mut self, command: Command) {
+        let Some(daemon) = self.selected_daemon() else { return };
+        let action = command.action();
+        self.message = Some(match command.run(&daemon) {
+            Ok(result) if result.success() => {
+                (Color::Green, format!("{} {}", action.past, daemon.name))
+            }
+            Ok(result) => (
+                Color::Red,
+                format!("failed to {} {}: {}", action.verb, daemon.name, result.stderr.trim()),
+            ),
+            Err(e) => (Color::Red, e.to_string()),
+        });
+        self.refresh();
+    }
+
+    fn draw(&mut self, out: &mut Stdout) -> Result<()> {
+        let (width, height) = terminal::size()?;
+        let (width, height) = (width as usize, height as usize);
+        let body = height.saturating_sub(3);
+
+        let visible = self.visible();
+        let index = self.selected_index(&visible);
+        let selected = visible.get(index).map(|daemon| (*daemon).clone());
+        let list_width = visible
+            .iter()
+            .map(|daemon| daemon.name.chars().count() + 20)
+            .max()
+            .unwrap_or(0)
+            .clamp(30, (width * 2 / 5).max(30));
+        let detail_width = width.saturating_sub(list_width + 3);
+
+        let offset = match () {
+            _ if index < self.offset => index,
+            _ if index >= self.offset + body => index + 1 - body,
+            _ => self.offset,
+        };
+        let rows: Vec<String> = visible
+            .iter()
+            .skip(offset)
+            .take(body)
+            .map(|daemon| {
+                let pid = daemon.pid.map_or("-".to_string(), |pid| pid.to_string());
+                format!("{:<10} {:>7}  {}", daemon.domain.to_string(), pid, daemon.name)
+            })
+            .collect();
+        let styles: Vec<(bool, Option<Color>)> = visible
+            .iter()
+            .skip(offset)
+            .take(body)
+            .map(|daemon| (daemon.pid.is_some(), exit_color(daemon)))
+            .collect();
+        let count = visible.len();
+        drop(visible);
+        self.offset = offset;
+
+        if self.detail.is_none() {
+            self.detail = Some(match &selected {
+                Some(daemon) => self.render_detail(daemon, body),
+                None => vec!["no services match".to_string()],
+            });
+        }
+        let detail = self.detail.as_deref().unwrap_or_default();
+        self.scroll = self.scroll.min(detail.len().saturating_sub(1));
+
+        queue!(out, MoveTo(0, 0), Print(fit(&self.header(count), width).bold()))?;
+        for row in 0..body {
+            queue!(out, MoveTo(0, (row + 1) as u16))?;
+            let text = fit(rows.get(row).map_or("", String::as_str), list_width);
+            match (row + self.offset == index && !rows.is_empty(), styles.get(row)) {
+                (true, _) => queue!(out, Print(text.reverse()))?,
+                (false, Some((true, _))) => queue!(out, Print(text.with(Color::Green)))?,
+                (false, Some((false, Some(color)))) => queue!(out, Print(text.with(*color)))?,
+                _ => queue!(out, Print(text))?,
+            }
+            let line = detail.get(row + self.scroll).map_or("", String::as_str);
+            queue!(
+                out,
+                Print(" │ ".dark_grey()),
+                Print(fit(line, detail_width)),
+                Clear(ClearType::UntilNewLine)
+            )?;
+        }
+
+        let (color, message) = match &self.message {
+            Some((color, message)) => (*color, message.as_str()),
+            None => (Color::Reset, ""),
+        };
+        queue!(
+            out,
+            MoveTo(0, (body + 1) as u16),
+            Print(fit(message, width).with(color)),
+            MoveTo(0, (body + 2) as u16),
+            Print(fit(HELP, width).dark_grey())
+        )?;
+        out.flush()?;
+        Ok(())
+    }
+
+    fn header(&self, count: usize) -> String {
+        let toggle = |on: bool| if on { "on" } else { "off" };
+        let filter = match (self.filtering, self.args.pattern.as_deref()) {
+            (true, pattern) => format!("/{}▏", pattern.unwrap_or_default()),
+            (false, Some(pattern)) if !pattern.is_empty() => format!("/{}", pattern),
+            (false, _) => "-".to_string(),
+        };
+        let domain = self
+            .args
+            .domain
+            .map_or("all".to_string(), |kind| kind.domain(self.angel.uid.as_raw()).to_string());
+        format!(
+            "angel  {} services  filter {}  domain {}  apple {}  dynamic {}  idle {}  [{}]",
+            count,
+            filter,
+            domain,
+            toggle(self.args.show_apple),
+            toggle(self.args.show_dynamic),
+            toggle(self.args.show_idle),
+            self.pane.title()
+        )
+    }
+
+    fn render_detail(&self, daemon: &Daemon, height: usize) -> Vec<String> {
+        let lines = match self.pane {
+            Pane::Plist => render_plist(daemon),
+            Pane::Print => match Parser::parse_print_service(daemon) {
+                Ok(Some(info)) => Ok(render_info(&info, daemon.disabled)),
+                Ok(None) => Ok(vec![format!("{} is not loaded", daemon.name)]),
+                Err(e) => Err(e),
+            },
+            Pane::Logs => logs::tail(daemon, height),
+        };
+        let lines = lines.unwrap_or_else(|e| vec![e.to_string()]);
+        lines.into_iter().map(|line| line.replace('\t', "    ")).collect()
+    }
+}
+
+/// Red for a service whose last exit wasn't clean.
+fn exit_color(daemon: &Daemon) -> Option<Color> {
+    let code = daemon.last_exit_code.as_deref()?;
+    match matches!(code, "0" | "-") {
+        true => None,
+        false => Some(Color::Red),
+    }
+}
+
+fn render_plist(daemon: &Daemon) -> Result<Vec<String>> {
+    let Some(path) = &daemon.source_path else {
+        return Ok(vec![format!("{} has no plist on disk", daemon.name)]);
+    };
+    // go through plist::Value so binary plists read as XML too
+    let mut buf = Vec::new();
+    plist::Value::from_file(path)?.to_writer_xml(&mut buf)?;
+    let mut lines = vec![path.display().to_string(), String::new()];
+    lines.extend(String::from_utf8_lossy(&buf).lines().map(str::to_string));
+    Ok(lines)
+}
+
+fn render_info(info: &ServiceInfo, disabled: Option<bool>) -> Vec<String> {
+    let mut lines = vec![info.target.clone(), String::new()];
+    let mut field = |name: &str, value: Option<String>| {
+        if let Some(value) = value {
+            lines.push(format!("{:<18} {}", format!("{}:", name), value));
+        }
+    };
+    field("state", Some(info.state.to_string()));
+    field("pid", info.pid.map(|pid| pid.to_string()));
+    field("disabled", disabled.map(|disabled| disabled.to_string()));
+    field("program", info.program.clone());
+    field("arguments", Some(info.arguments.join(" ")).filter(|args| !args.is_empty()));
+    field("working directory", info.working_directory.clone());
+    field("stdout", info.stdout_path.clone());
+    field("stderr", info.stderr_path.clone());
+    field("last exit code", info.last_exit_code.clone());
+    field("runs", info.runs.map(|runs| runs.to_string()));
+    field("run interval", info.run_interval.clone());
+    field("spawn type", info.spawn_type.clone());
+    field("properties", Some(info.properties.join(" | ")).filter(|p| !p.is_empty()));
+    for (key, value) in &info.other {
+        field(key, Some(value.clone()));
+    }
+    if !info.environment.is_empty() {
+        lines.push(String::new());
+        lines.push("environment:".to_string());
+        lines.extend(info.environment.iter().map(|(key, value)| format!("  {}={}", key, value)));
+    }
+    if !info.endpoints.is_empty() {
+        lines.push(String::new());
+        lines.push("endpoints:".to_string());
+        lines.extend(info.endpoints.iter().map(|endpoint| format!("  {}", endpoint.name)));
+    }
+    lines
+}
+
+/// Pad or cut `text` to exactly `width` columns.
+fn fit(text: &str, width: usize) -> String {
+    let mut fitted: String = text.chars().take(width).collect();
+    let len = fitted.chars().count();
+    fitted.extend(std::iter::repeat_n(' ', width - len));
+    fitted
+}
@@ -1,25 +1,49 @@
 use crate::angel::Angel;
-use crate::cli::NameArgs;
+use crate::cli::UninstallArgs;
+use crate::commands::install::{InstallRecord, InstallRecords, InstallStrategy};
+use crate::commands::overrides;
 use crate::error::Result;
 use crate::launchctl;
 use crate::output::{is_verbose, stderr, stdout};
+use crate::overrides::OverrideDb;
 use crate::parser::Parser;
+use crate::prompt;
 use crate::types::Daemon;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
-pub fn run(angel: &Angel, args: &NameArgs) -> Result<()> {
-    let daemon = angel.daemons.get_match(&args.name, args.exact)?;
+/// What happens to the plist file, depending on how `install` put it there.
+enum Removal {
+    Keep,
+    /// Symlinked: remove the link, never what it points to
+    Unlink,
+    /// Moved: put it back where it came from
+    Restore(PathBuf),
+    Delete,
+}
+
+pub fn run(angel: &Angel, args: &UninstallArgs) -> Result<()> {
+    let target = &args.target;
+    let daemon = angel.daemons.get_match(
+        &target.name,
+        target.exact,
+        angel.domain(target.domain).as_ref(),
+    )?;
 
     let source_path = get_source_path(daemon)?;
+    let mut records = InstallRecords::load(angel)?;
+    let removal = get_removal(args.keep_file, records.get(&source_path), &source_path);
 
-    if !confirm_uninstall(daemon, &source_path)? {
+    if !confirm_uninstall(daemon, &source_path, &removal)? {
         stdout::writeln("Uninstall cancelled.");
         return Ok(());
     }
 
     bootout_service(daemon);
-    remove_plist_file(&source_path)?;
-    remove_db_overrides(daemon)?;
+    if remove_plist_file(&source_path, removal)? {
+        records.remove(&source_path);
+        records.save(angel)?;
+    }
+    remove_db_overrides(angel, daemon)?;
 
     stdout::success(&format!("Uninstalled {}", daemon.name));
     Ok(())
@@ -32,17 +56,39 @@ fn get_source_path(daemon: &Daemon) -> Result<PathBuf> {
     }
 
     // Fall back to parsing launchctl print output
-    Parser::parse_print_service(daemon)?.and_then(|(_, _, _, path)| path).ok_or_else(|| {
+    Parser::parse_print_service(daemon)?.and_then(|info| info.path).ok_or_else(|| {
         crate::error::AngelError::from(crate::error::UserError::InvalidArgument(
             "Service does not have an installed plist file".to_string(),
         ))
     })
 }
 
-fn confirm_uninstall(daemon: &Daemon, source_path: &PathBuf) -> Result<bool> {
-    Ok(dialoguer::Confirm::new()
-        .with_prompt(format!("Uninstall service `{}` at `{}`?", daemon.name, source_path.display()))
-        .interact()?)
+fn get_removal(keep_file: bool, record: Option<&InstallRecord>, source_path: &Path) -> Removal {
+    let is_symlink = source_path.symlink_metadata().is_ok_and(|meta| meta.file_type().is_symlink());
+    match record.map(|record| (&record.strategy, &record.source)) {
+        _ if keep_file => Removal::Keep,
+        _ if is_symlink => Removal::Unlink,
+        Some((InstallStrategy::Move, source)) => Removal::Restore(source.clone()),
+        _ => Removal::Delete,
+    }
+}
+
+fn confirm_uninstall(daemon: &Daemon, source_path: &Path, removal: &Removal) -> Result<bool> {
+    let question = match removal {
+        Removal::Keep => {
+            format!("Uninstall service `{}`, keeping `{}`?", daemon.name, source_path.display())
+        }
+        Removal::Restore(original) => format!(
+            "Uninstall service `{}` and move `{}` back to `{}`?",
+            daemon.name,
+            source_path.display(),
+            original.display()
+        ),
+        Removal::Unlink | Removal::Delete => {
+            format!("Uninstall service `{}` at `{}`?", daemon.name, source_path.display())
+        }
+    };
+    prompt::confirm(&question, false)
 }
 
 fn bootout_service(daemon: &Daemon) {
@@ -62,48 +108,62 @@ fn bootout_service(daemon: &Daemon) {
     }
 }
 
-fn remove_plist_file(source_path: &PathBuf) -> Result<()> {
+/// Returns whether the file is gone from `source_path`, so its install record can go too.
+fn remove_plist_file(source_path: &Path, removal: Removal) -> Result<bool> {
     let source_path_display = source_path.display().to_string();
-    if source_path.exists() {
-        std::fs::remove_file(source_path)?;
-        stdout::success(&format!("Removed plist file: {}", source_path_display));
-    } else if is_verbose() {
-        stderr::warn(&format!("Warning: Plist file does not exist: {}", source_path_display));
+    if source_path.symlink_metadata().is_err() {
+        if is_verbose() {
+            stderr::warn(&format!("Warning: Plist file does not exist: {}", source_path_display));
+        }
+        return Ok(true);
     }
-    Ok(())
+    match removal {
+        Removal::Keep => {
+            stdout::hint(&format!("Kept plist file: {}", source_path_display));
+            return Ok(false);
+        }
+        Removal::Unlink => {
+            std::fs::remove_file(source_path)?;
+            stdout::success(&format!("Removed symlink: {}", source_path_display));
+        }
+        Removal::Restore(original) if original.symlink_metadata().is_ok() => {
+            stderr::warn(&format!(
+                "{} already exists; leaving the plist at {}",
+                original.display(),
+                source_path_display
+            ));
+            return Ok(false);
+        }
+        Removal::Restore(original) => {
+            if let Some(parent) = original.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::rename(source_path, &original)?;
+            stdout::success(&format!("Moved plist file back to {}", original.display()));
+        }
+        Removal::Delete => {
+            std::fs::remove_file(source_path)?;
+            stdout::success(&format!("Removed plist file: {}", source_path_display));
+        }
+    }
+    Ok(true)
 }
 
-fn remove_db_overrides(daemon: &Daemon) -> Result<()> {
-    let db_overrides_file = PathBuf::from("/var/db/com.apple.xpc.launchd/disabled.plist");
-    if !db_overrides_file.exists() {
+fn remove_db_overrides(angel: &Angel, daemon: &Daemon) -> Result<()> {
+    let mut db = OverrideDb::for_domain(&angel.paths, &daemon.domain)?;
+    let Some(current_value) = db.overrides.get(&daemon.name).copied() else { return Ok(()) };
+    if !confirm_db_overrides(daemon, &current_value)? {
         return Ok(());
     }
-
-    let bytes = std::fs::read(&db_overrides_file)?;
-    let disabled_services: std::collections::HashMap<String, bool> = plist::from_bytes(&bytes)?;
-    stdout::writeln(&format!("disabled_services: {:?}", disabled_services));
-
-    let current_value = disabled_services.get(&daemon.name);
-    if current_value.is_some() {
-        if !confirm_db_overrides(daemon, current_value.unwrap())? {
-            return Ok(());
-        }
-        let mut updated_services = disabled_services.clone();
-        updated_services.remove(&daemon.name);
-        let mut writer =
-            std::fs::OpenOptions::new().write(true).truncate(true).open(&db_overrides_file)?;
-        plist::to_writer_xml(&mut writer, &updated_services)?;
-        stdout::success(&format!("Removed service from disabled.plist: {}", daemon.name));
-    }
-
-    Ok(())
+    overrides::clear_override(&mut db, &daemon.name)
 }
 
 fn confirm_db_overrides(daemon: &Daemon, current_value: &bool) -> Result<bool> {
-    Ok(dialoguer::Confirm::new()
-        .with_prompt(format!(
+    prompt::confirm(
+        &format!(
             "Found `{}` in enable/disable override database with disabled = {}. Remove it?",
             daemon.name, current_value
-        ))
-        .interact()?)
+        ),
+        false,
+    )
 }
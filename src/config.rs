@@ -5,6 +5,8 @@ use std::path::PathBuf;
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct Config {
+    /// Filesystem root all launchd paths are resolved under, see `paths::Paths`
+    pub root: Option<String>,
     pub directories: Option<Vec<DirectoryConfig>>,
     pub colors: Option<Colors>,
 }
@@ -65,36 +67,30 @@ pub struct Colors {
 impl Config {
     pub fn load() -> Result<Config> {
         let home = std::env::var("HOME").unwrap_or_default();
-        
+
         // Skip config loading if running as root
         if home.is_empty() || home == "/var/root" {
-            return Ok(Config {
-                directories: None,
-                colors: None,
-            });
+            return Ok(Config { root: None, directories: None, colors: None });
         }
 
         // Try config file locations
         let config_paths = vec![
             PathBuf::from(&home).join(".angelrc"),
-            dirs::config_dir()
-                .map(|p| p.join("angel").join(".angelrc"))
-                .unwrap_or_default(),
+            dirs::config_dir().map(|p| p.join("angel").join(".angelrc")).unwrap_or_default(),
             PathBuf::from(&home).join(".config").join("angel").join(".angelrc"),
         ];
 
         for path in config_paths {
             if path.exists() {
                 let content = std::fs::read_to_string(&path)?;
-                let mut config: Config = serde_yaml::from_str(&content)
-                    .map_err(|e| SystemError::Config(anyhow::anyhow!("Failed to parse config: {}", e)))?;
+                let mut config: Config = serde_yaml::from_str(&content).map_err(|e| {
+                    SystemError::Config(anyhow::anyhow!("Failed to parse config: {}", e))
+                })?;
 
                 // Expand ~ in directory paths
                 if let Some(ref mut dirs) = config.directories {
                     for dir in dirs.iter_mut() {
-                        if dir.path.starts_with('~') {
-                            dir.path = dir.path.replacen('~', &home, 1);
-                        }
+                        dir.path = expand_tilde(&dir.path).to_string_lossy().to_string();
                     }
                 }
 
@@ -103,10 +99,7 @@ impl Config {
         }
 
         // Config file is optional
-        Ok(Config {
-            directories: None,
-            colors: None,
-        })
+        Ok(Config { root: None, directories: None, colors: None })
     }
 
     pub fn get_directories(&self) -> Vec<DirectoryConfig> {
@@ -114,3 +107,13 @@ impl Config {
     }
 }
 
+/// Expand a leading `~` or `~/` to `$HOME`
+pub fn expand_tilde(path: &str) -> PathBuf {
+    let home = std::env::var("HOME").unwrap_or_default();
+    match path.strip_prefix('~') {
+        Some(rest) if !home.is_empty() && (rest.is_empty() || rest.starts_with('/')) => {
+            PathBuf::from(format!("{}{}", home, rest))
+        }
+        _ => PathBuf::from(path),
+    }
+}
@@ -1,82 +1,106 @@
 use crate::config::Config;
 use crate::error::{Result, SystemError, UserError};
-use crate::styles::styles;
+use crate::overrides::OverrideDb;
 use crate::parser::Parser;
-use crate::types::{Daemon, Domain, ForWhom, Plist};
+use crate::paths::Paths;
+use crate::prompt;
+use crate::styles::styles;
+use crate::types::{Daemon, Domain, DomainInfo, DomainService, ForWhom, Plist};
 use regex::Regex;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
 use std::path::PathBuf;
 use std::thread;
 
-struct PlistDir {
-    path: PathBuf,
-    domain: Domain,
-    for_use_by: ForWhom,
-    is_system_monitored: bool,
+/// Why a plist file found while scanning didn't make it into the registry as-is.
+#[derive(Debug, Clone)]
+pub enum ScanIssue {
+    Unreadable(String),
+    ParseFailed(String),
+    /// The label is also defined by `kept`, which won
+    DuplicateLabel {
+        label: String,
+        kept: PathBuf,
+    },
+}
+
+#[derive(Debug, Clone)]
+pub struct ScanDiagnostic {
+    pub path: PathBuf,
+    pub issue: ScanIssue,
+}
+
+impl fmt::Display for ScanDiagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let path = self.path.display();
+        match &self.issue {
+            ScanIssue::Unreadable(e) => write!(f, "{}: could not be read: {}", path, e),
+            ScanIssue::ParseFailed(e) => write!(f, "{}: could not be parsed: {}", path, e),
+            ScanIssue::DuplicateLabel { label, kept } => {
+                write!(f, "{}: duplicate label {}, using {}", path, label, kept.display())
+            }
+        }
+    }
+}
+
+pub struct PlistDir {
+    pub path: PathBuf,
+    pub domain: Domain,
+    pub for_use_by: ForWhom,
+    pub is_system_monitored: bool,
 }
 
-fn get_plist_dirs(config: &Config, user_uid: u32) -> Vec<PlistDir> {
-    let home = std::env::var("HOME").unwrap_or_default();
+pub fn get_plist_dirs(config: &Config, paths: &Paths, user_uid: u32) -> Vec<PlistDir> {
     let mut dirs = vec![
         PlistDir {
-            path: PathBuf::from("/System/Library/LaunchDaemons"),
+            path: paths.apple_launch_daemons(),
             domain: Domain::System,
             for_use_by: ForWhom::Apple,
             is_system_monitored: true,
         },
         PlistDir {
-            path: PathBuf::from("/System/Library/LaunchAgents"),
+            path: paths.apple_launch_agents(),
             domain: Domain::Gui(user_uid),
             for_use_by: ForWhom::Apple,
             is_system_monitored: true,
         },
         PlistDir {
-            path: PathBuf::from("/System/Library/LaunchAngels"),
+            path: paths.apple_launch_angels(),
             domain: Domain::Gui(user_uid),
             for_use_by: ForWhom::Apple,
             is_system_monitored: true,
         },
         PlistDir {
-            path: PathBuf::from("/Library/LaunchDaemons"),
+            path: paths.launch_daemons(),
             domain: Domain::System,
             for_use_by: ForWhom::ThirdParty,
             is_system_monitored: true,
         },
         PlistDir {
-            path: PathBuf::from("/Library/LaunchAgents"),
+            path: paths.launch_agents(),
             domain: Domain::User(user_uid),
             for_use_by: ForWhom::ThirdParty,
             is_system_monitored: true,
         },
     ];
 
-    if !home.is_empty() {
-        dirs.extend(vec![
-            PlistDir {
-                path: PathBuf::from(&home).join("Library/LaunchAgents"),
-                domain: Domain::User(user_uid),
-                for_use_by: ForWhom::User,
-                is_system_monitored: true,
-            },
-            PlistDir {
-                path: PathBuf::from(&home).join(".config/angel/user"),
-                domain: Domain::User(user_uid),
-                for_use_by: ForWhom::Angel,
-                is_system_monitored: false,
-            },
-            PlistDir {
-                path: PathBuf::from(&home).join(".config/angel/system"),
-                domain: Domain::System,
-                for_use_by: ForWhom::Angel,
-                is_system_monitored: false,
-            },
-            PlistDir {
-                path: PathBuf::from(&home).join(".config/angel/gui"),
-                domain: Domain::Gui(user_uid),
-                for_use_by: ForWhom::Angel,
-                is_system_monitored: false,
-            },
-        ]);
+    if let Some(user_launch_agents) = paths.user_launch_agents() {
+        dirs.push(PlistDir {
+            path: user_launch_agents,
+            domain: Domain::User(user_uid),
+            for_use_by: ForWhom::User,
+            is_system_monitored: true,
+        });
+        for domain in [Domain::User(user_uid), Domain::System, Domain::Gui(user_uid)] {
+            if let Some(path) = paths.angel_dir(&domain) {
+                dirs.push(PlistDir {
+                    path,
+                    domain,
+                    for_use_by: ForWhom::Angel,
+                    is_system_monitored: false,
+                });
+            }
+        }
     }
 
     // Add user-defined directories from config
@@ -89,7 +113,7 @@ fn get_plist_dirs(config: &Config, user_uid: u32) -> Vec<PlistDir> {
             Domain::Unknown => Domain::Unknown,
         };
         dirs.push(PlistDir {
-            path: PathBuf::from(&cfg_dir.path),
+            path: paths.resolve(&cfg_dir.path),
             domain,
             for_use_by: ForWhom::User,
             is_system_monitored: false,
@@ -99,101 +123,227 @@ fn get_plist_dirs(config: &Config, user_uid: u32) -> Vec<PlistDir> {
     dirs
 }
 
+fn same_file(a: &std::path::Path, b: &std::path::Path) -> bool {
+    match (a.canonicalize(), b.canonicalize()) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => false,
+    }
+}
+
+/// A plist scanned into one per-user domain may be loaded into the other, e.g. an agent in
+/// `~/Library/LaunchAgents` running in `gui/<uid>`. Move it to the domain it actually runs in,
+/// unless it is loaded there too.
+fn adopt_from_sibling(
+    map: &mut HashMap<(Domain, String), Daemon>,
+    domains: &HashMap<Domain, DomainInfo>,
+    domain: &Domain,
+    name: &str,
+) {
+    let Some(sibling) = domain.sibling() else { return };
+    let loaded_in_sibling = domains
+        .get(&sibling)
+        .is_some_and(|info| info.services.iter().any(|service| service.label == name));
+    if loaded_in_sibling {
+        return;
+    }
+    if let Some(mut daemon) = map.remove(&(sibling, name.to_string())) {
+        daemon.domain = domain.clone();
+        map.insert((domain.clone(), name.to_string()), daemon);
+    }
+}
+
+#[derive(Clone)]
 pub struct DaemonRegistry {
-    map: HashMap<String, Daemon>,
+    /// Keyed by (domain, label): the same label can be defined in several domains
+    map: HashMap<(Domain, String), Daemon>,
+    domains: HashMap<Domain, DomainInfo>,
+    /// label -> disabled, per domain, from the override database on disk. Usually only
+    /// readable as root; the domain print's `disabled services` block is preferred anyway.
+    overrides: HashMap<Domain, BTreeMap<String, bool>>,
+    diagnostics: Vec<ScanDiagnostic>,
 }
 
 impl DaemonRegistry {
-    pub fn new(config: &Config, uid: u32) -> Result<Self> {
-        let plist_dirs = get_plist_dirs(config, uid);
-        let mut map: HashMap<String, Daemon> = HashMap::new();
+    pub fn new(config: &Config, paths: &Paths, uid: u32) -> Result<Self> {
+        let plist_dirs = get_plist_dirs(config, paths, uid);
+        let mut map: HashMap<(Domain, String), Daemon> = HashMap::new();
+        let mut diagnostics = Vec::new();
 
         // Scan plist directories
         for plist_dir in &plist_dirs {
             let pattern = format!("{}/*.plist", plist_dir.path.display());
             let matches = glob::glob(&pattern).unwrap_or_else(|_| glob::glob("").unwrap());
 
-            for entry in matches.flatten() {
+            for entry in matches {
+                let entry = match entry {
+                    Ok(entry) => entry,
+                    Err(e) => {
+                        let issue = ScanIssue::Unreadable(e.error().to_string());
+                        diagnostics.push(ScanDiagnostic { path: e.path().to_path_buf(), issue });
+                        continue;
+                    }
+                };
                 let plist_uid = match plist_dir.domain {
                     Domain::User(uid) | Domain::Gui(uid) => uid,
                     _ => 0,
                 };
-                if let Ok(content) = std::fs::read(&entry) {
-                    if let Ok(plist_data) = plist::from_bytes::<Plist>(&content) {
-                        // pick source path for duplicates
-                        let found_daemon = map.get(&plist_data.label.clone().unwrap_or_default());
-                        let found_path = found_daemon.and_then(|daemon| daemon.source_path.clone());
-                        let source_path = match found_path {
-                            Some(found_path) => match () {
-                                _ if entry.is_symlink() => entry,
-                                _ if found_path.is_symlink() => found_path,
-                                _ if plist_dir.is_system_monitored => found_path,
-                                _ => entry,
-                            },
-                            None => entry,
-                        };
-
-                        let daemon = Daemon::from_plist(
-                            plist_data,
-                            Some(source_path),
-                            plist_dir.domain.clone(),
-                            plist_dir.for_use_by,
-                            plist_uid,
-                        );
-                        map.insert(daemon.name.clone(), daemon);
+                let content = match std::fs::read(&entry) {
+                    Ok(content) => content,
+                    Err(e) => {
+                        let issue = ScanIssue::Unreadable(e.to_string());
+                        diagnostics.push(ScanDiagnostic { path: entry, issue });
+                        continue;
                     }
+                };
+                let plist_data = match plist::from_bytes::<Plist>(&content) {
+                    Ok(plist_data) => plist_data,
+                    Err(e) => {
+                        let issue = ScanIssue::ParseFailed(e.to_string());
+                        diagnostics.push(ScanDiagnostic { path: entry, issue });
+                        continue;
+                    }
+                };
+
+                let mut daemon = Daemon::from_plist(
+                    plist_data,
+                    Some(entry.clone()),
+                    plist_dir.domain.clone(),
+                    plist_dir.for_use_by,
+                    plist_uid,
+                );
+                let key = (daemon.domain.clone(), daemon.name.clone());
+
+                // pick source path for duplicates within a domain
+                let found_path = map.get(&key).and_then(|daemon| daemon.source_path.clone());
+                if let Some(found_path) = found_path {
+                    let kept = match () {
+                        _ if entry.is_symlink() => entry.clone(),
+                        _ if found_path.is_symlink() => found_path.clone(),
+                        _ if plist_dir.is_system_monitored => found_path.clone(),
+                        _ => entry.clone(),
+                    };
+                    // a symlink to the other file is how `install` links a plist in
+                    if !same_file(&entry, &found_path) {
+                        let dropped = if kept == entry { found_path } else { entry };
+                        let label = daemon.name.clone();
+                        let issue = ScanIssue::DuplicateLabel { label, kept: kept.clone() };
+                        diagnostics.push(ScanDiagnostic { path: dropped, issue });
+                    }
+                    daemon.source_path = Some(kept);
                 }
+                map.insert(key, daemon);
             }
         }
 
+        let overrides = [Domain::System, Domain::User(uid), Domain::Gui(uid)]
+            .into_iter()
+            .filter_map(|domain| {
+                let db = OverrideDb::for_domain(paths, &domain).ok()?;
+                Some((domain, db.overrides))
+            })
+            .collect();
+        let mut registry = Self { map, domains: HashMap::new(), overrides, diagnostics };
+        registry.load_runtime(uid);
+        Ok(registry)
+    }
+
+    /// Re-read the pid and last exit code of every job from `launchctl print`, without
+    /// rescanning plist directories. Jobs with no plist that are no longer loaded are dropped.
+    pub fn refresh(&mut self, uid: u32) {
+        self.map.retain(|_, daemon| daemon.source_path.is_some());
+        for daemon in self.map.values_mut() {
+            daemon.pid = None;
+            daemon.last_exit_code = None;
+        }
+        self.load_runtime(uid);
+    }
+
+    fn load_runtime(&mut self, uid: u32) {
         // Add running daemons from launchctl print (parallelized)
         let domains = vec![Domain::System, Domain::User(uid), Domain::Gui(uid)];
         let handles: Vec<_> = domains
             .into_iter()
             .map(|domain| {
                 thread::spawn(move || {
-                    Parser::parse_print_domain(&domain).ok().map(|services| (domain, services))
+                    Parser::parse_print_domain(&domain).ok().flatten().map(|info| (domain, info))
                 })
             })
             .collect();
 
-        for handle in handles {
-            if let Some((domain, services)) = handle.join().unwrap() {
-                for (pid, last_exit_code, name) in services {
-                    // Update existing daemon or create new one
-                    if let Some(daemon) = map.get_mut(&name) {
-                        daemon.pid = pid;
-                        daemon.last_exit_code = last_exit_code;
+        self.domains = handles.into_iter().filter_map(|handle| handle.join().unwrap()).collect();
+
+        for (domain, info) in &self.domains {
+            for service in &info.services {
+                let DomainService { pid, last_exit_code, label: name } = service.clone();
+                let key = (domain.clone(), name.clone());
+                if !self.map.contains_key(&key) {
+                    adopt_from_sibling(&mut self.map, &self.domains, domain, &name);
+                }
+                // Update existing daemon or create new one
+                if let Some(daemon) = self.map.get_mut(&key) {
+                    daemon.pid = pid;
+                    daemon.last_exit_code = last_exit_code;
+                } else {
+                    let for_use_by = if name.contains("com.apple") {
+                        ForWhom::Apple
                     } else {
-                        let for_use_by = if name.contains("com.apple") {
-                            ForWhom::Apple
-                        } else {
-                            ForWhom::ThirdParty
-                        };
-                        let daemon = Daemon::new(
-                            name.clone(),
-                            None,
-                            domain.clone(),
-                            for_use_by,
-                            None,
-                            pid,
-                            last_exit_code,
-                        );
-                        map.insert(name, daemon);
-                    }
+                        ForWhom::ThirdParty
+                    };
+                    let daemon = Daemon::new(
+                        name,
+                        None,
+                        domain.clone(),
+                        for_use_by,
+                        None,
+                        pid,
+                        last_exit_code,
+                    );
+                    self.map.insert(key, daemon);
                 }
             }
         }
+        self.load_disabled();
+    }
 
-        Ok(Self { map })
+    /// Fill in each daemon's `disabled` from its domain's `disabled services` block, then the
+    /// override database, then the plist's `Disabled` key.
+    fn load_disabled(&mut self) {
+        for ((domain, name), daemon) in self.map.iter_mut() {
+            let printed =
+                self.domains.get(domain).and_then(|info| info.disabled_services.get(name));
+            let stored = self.overrides.get(domain).and_then(|overrides| overrides.get(name));
+            let default = daemon.plist.as_ref().and_then(|plist| plist.disabled);
+            daemon.disabled = printed.or(stored).copied().or(default);
+        }
     }
 
-    pub fn get_match(&self, query: &str, exact: bool) -> Result<&Daemon> {
-        let matches = self.find_matches(query, exact)?;
+    /// Problems found while scanning plist directories, in scan order.
+    pub fn diagnostics(&self) -> &[ScanDiagnostic] {
+        &self.diagnostics
+    }
+
+    /// The parsed `launchctl print` output of a domain, if it could be read.
+    pub fn domain_info(&self, domain: &Domain) -> Option<&DomainInfo> {
+        self.domains.get(domain)
+    }
+
+    /// Resolve `query` to one daemon. `query` is a name pattern, or a fully qualified target
+    /// like `gui/501/com.foo`; `domain` restricts the search to one domain.
+    pub fn get_match(&self, query: &str, exact: bool, domain: Option<&Domain>) -> Result<&Daemon> {
+        let matches = self.find_matches(query, exact, domain)?;
         match matches.len() {
             0 => Err(UserError::DaemonNotFound(query.to_string()).into()),
             1 => Ok(matches[0]),
             _ => {
+                if !prompt::is_interactive() {
+                    let candidates = matches
+                        .iter()
+                        .map(|daemon| format!("{}/{}", daemon.domain, daemon.name))
+                        .collect();
+                    let query = query.to_string();
+                    return Err(UserError::AmbiguousMatch { query, candidates }.into());
+                }
+
                 // Format daemons for display
                 let items: Vec<String> = matches
                     .iter()
@@ -202,30 +352,45 @@ impl DaemonRegistry {
                             "{:<19}{}  {}",
                             styles::color_domain(&daemon.domain),
                             daemon.name,
-                            styles::command(&styles::display_path(&daemon, false))
+                            styles::command(&styles::display_path(daemon, false))
                         )
                     })
                     .collect();
 
-                let selection = dialoguer::Select::new()
-                    .with_prompt(format!(
-                        "Multiple daemons found matching '{}'. Select one:",
-                        query
-                    ))
-                    .items(&items)
-                    .default(0)
-                    .interact()?;
+                let selection = prompt::select(
+                    &format!("Multiple daemons found matching '{}'. Select one:", query),
+                    &items,
+                    0,
+                    "use --exact",
+                )?;
 
                 Ok(matches[selection])
             }
         }
     }
 
-    pub fn get_matches(&self, query: &str, exact: bool) -> Result<Vec<&Daemon>> {
-        self.find_matches(query, exact)
+    pub fn get_matches(
+        &self,
+        query: &str,
+        exact: bool,
+        domain: Option<&Domain>,
+    ) -> Result<Vec<&Daemon>> {
+        self.find_matches(query, exact, domain)
     }
 
-    fn find_matches(&self, query: &str, exact: bool) -> Result<Vec<&Daemon>> {
+    fn find_matches(
+        &self,
+        query: &str,
+        exact: bool,
+        domain: Option<&Domain>,
+    ) -> Result<Vec<&Daemon>> {
+        if let Some((target_domain, label)) = Domain::parse_target(query) {
+            if domain.is_some_and(|domain| *domain != target_domain) {
+                return Ok(Vec::new());
+            }
+            return Ok(self.map.get(&(target_domain, label.to_string())).into_iter().collect());
+        }
+
         let pattern = if exact {
             format!("^{}$", regex::escape(query))
         } else {
@@ -235,6 +400,11 @@ impl DaemonRegistry {
         let re = Regex::new(&pattern)
             .map_err(|e| SystemError::Launchctl(format!("Invalid regex: {}", e)))?;
 
-        Ok(self.map.values().filter(|daemon| re.is_match(&daemon.name)).collect())
+        Ok(self
+            .map
+            .values()
+            .filter(|daemon| domain.is_none_or(|domain| daemon.domain == *domain))
+            .filter(|daemon| re.is_match(&daemon.name))
+            .collect())
     }
 }
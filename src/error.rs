@@ -12,6 +12,21 @@ pub enum UserError {
 
     #[error("Invalid argument: {0}")]
     InvalidArgument(String),
+
+    #[error("Lint failed: {0} plist(s) with errors")]
+    LintFailed(usize),
+
+    #[error("{0}")]
+    ActionFailed(String),
+
+    #[error("Input required but prompts are disabled: {0}")]
+    InputRequired(String),
+
+    #[error("'{query}' matches several services, narrow it down or use --exact:\n  {}", .candidates.join("\n  "))]
+    AmbiguousMatch { query: String, candidates: Vec<String> },
+
+    #[error("{failed} of {total} services failed")]
+    BatchFailed { failed: usize, total: usize },
 }
 
 /// System/internal errors - I/O failures, parsing errors, system call failures
@@ -35,6 +50,9 @@ pub enum SystemError {
 
     #[error("Launchctl error: {0}")]
     Launchctl(String),
+
+    #[error("Serialization error: {0}")]
+    Serialize(String),
 }
 
 /// Unified error type that can be either a user error or system error
@@ -0,0 +1,367 @@
+use super::{LaunchctlBackend, LaunchctlResult};
+use crate::error::Result;
+use crate::types::{KeepAlive, Plist};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::path::PathBuf;
+use std::sync::{Mutex, MutexGuard};
+
+/// In-memory stand-in for launchd. It understands the subset of launchctl that angel issues and
+/// answers `print` with output in launchctl's own format, so the parser runs against it too.
+pub struct FakeLaunchctl {
+    state: Mutex<FakeState>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct FakeService {
+    pub label: String,
+    pub path: Option<PathBuf>,
+    pub program: Option<String>,
+    pub arguments: Vec<String>,
+    pub environment: BTreeMap<String, String>,
+    pub keep_alive: bool,
+    pub run_at_load: bool,
+    pub pid: Option<u32>,
+    pub last_exit_code: Option<i32>,
+    pub runs: u32,
+}
+
+#[derive(Default)]
+struct FakeDomain {
+    services: BTreeMap<String, FakeService>,
+    /// label -> disabled, as reported in the domain's `disabled services` block
+    overrides: BTreeMap<String, bool>,
+}
+
+struct FakeState {
+    domains: BTreeMap<String, FakeDomain>,
+    next_pid: u32,
+    calls: Vec<Vec<String>>,
+}
+
+impl Default for FakeLaunchctl {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FakeLaunchctl {
+    /// A fake with an empty `system` domain. Per-user domains are added with `with_domain`.
+    pub fn new() -> Self {
+        let mut domains = BTreeMap::new();
+        domains.insert("system".to_string(), FakeDomain::default());
+        Self { state: Mutex::new(FakeState { domains, next_pid: 100, calls: Vec::new() }) }
+    }
+
+    pub fn with_domain(self, domain: &str) -> Self {
+        self.lock().domains.entry(domain.to_string()).or_default();
+        self
+    }
+
+    /// Load a service directly, bypassing bootstrap. A `pid` marks it as running.
+    pub fn add_service(&self, domain: &str, service: FakeService) {
+        let mut state = self.lock();
+        let domain = state.domains.entry(domain.to_string()).or_default();
+        domain.services.insert(service.label.clone(), service);
+    }
+
+    pub fn service(&self, target: &str) -> Option<FakeService> {
+        let (domain, label) = split_target(target)?;
+        self.lock().domains.get(domain)?.services.get(label).cloned()
+    }
+
+    pub fn is_disabled(&self, target: &str) -> bool {
+        split_target(target)
+            .and_then(|(domain, label)| {
+                self.lock().domains.get(domain)?.overrides.get(label).copied()
+            })
+            .unwrap_or(false)
+    }
+
+    /// Simulate the service's process exiting on its own.
+    pub fn exit_service(&self, target: &str, exit_code: i32) {
+        let mut state = self.lock();
+        let next_pid = state.next_pid;
+        if let Some(service) = state.service_mut(target) {
+            service.pid = None;
+            service.last_exit_code = Some(exit_code);
+            let relaunch = service.keep_alive;
+            if relaunch {
+                service.pid = Some(next_pid);
+                service.runs += 1;
+                state.next_pid += 1;
+            }
+        }
+    }
+
+    /// Every invocation received so far, as argument lists.
+    pub fn calls(&self) -> Vec<Vec<String>> {
+        self.lock().calls.clone()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, FakeState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl LaunchctlBackend for FakeLaunchctl {
+    fn exec(&self, args: &[&str]) -> Result<LaunchctlResult> {
+        let mut state = self.lock();
+        state.calls.push(args.iter().map(|a| a.to_string()).collect());
+        Ok(match args {
+            ["bootstrap", domain, path] => state.bootstrap(domain, path),
+            ["bootout", target] => state.bootout(target),
+            ["enable", target] => state.set_disabled(target, false),
+            ["disable", target] => state.set_disabled(target, true),
+            ["kickstart", target] => state.kickstart(target, false),
+            ["kickstart", "-k", target] => state.kickstart(target, true),
+            ["kill", signal, target] => state.kill(target, signal),
+            ["print", target] => state.print(target),
+            _ => failure(64, &format!("Unrecognized subcommand: {}", args.join(" "))),
+        })
+    }
+}
+
+impl FakeState {
+    fn service_mut(&mut self, target: &str) -> Option<&mut FakeService> {
+        let (domain, label) = split_target(target)?;
+        self.domains.get_mut(domain)?.services.get_mut(label)
+    }
+
+    fn start(&mut self, target: &str) {
+        let pid = self.next_pid;
+        if let Some(service) = self.service_mut(target) {
+            service.pid = Some(pid);
+            service.runs += 1;
+            self.next_pid += 1;
+        }
+    }
+
+    fn bootstrap(&mut self, domain: &str, path: &str) -> LaunchctlResult {
+        let plist = match std::fs::read(path).ok().and_then(|b| plist::from_bytes::<Plist>(&b).ok())
+        {
+            Some(plist) => plist,
+            None => return failure(5, "Bootstrap failed: 5: Input/output error"),
+        };
+        let Some(label) = plist.label.clone() else {
+            return failure(5, "Bootstrap failed: 5: Input/output error");
+        };
+        let Some(fake_domain) = self.domains.get_mut(domain) else {
+            return failure(125, "Bootstrap failed: 125: Domain does not support specified action");
+        };
+        if fake_domain.overrides.get(&label) == Some(&true) {
+            return failure(119, "Bootstrap failed: 119: Service is disabled");
+        }
+        if fake_domain.services.contains_key(&label) {
+            return failure(5, "Bootstrap failed: 5: Input/output error");
+        }
+
+        let arguments = plist.program_arguments.clone().unwrap_or_default();
+        let service = FakeService {
+            label: label.clone(),
+            path: Some(PathBuf::from(path)),
+            program: plist.program.clone().or_else(|| arguments.first().cloned()),
+            arguments,
+            environment: plist.environment_variables.clone().unwrap_or_default(),
+            keep_alive: plist.keep_alive.as_ref().is_some_and(|k| k != &KeepAlive::Always(false)),
+            run_at_load: plist.run_at_load.unwrap_or(false),
+            ..Default::default()
+        };
+        let starts = service.run_at_load || service.keep_alive;
+        fake_domain.services.insert(label.clone(), service);
+        if starts {
+            self.start(&format!("{}/{}", domain, label));
+        }
+        success("")
+    }
+
+    fn bootout(&mut self, target: &str) -> LaunchctlResult {
+        let removed = split_target(target).and_then(|(domain, label)| {
+            self.domains.get_mut(domain).and_then(|d| d.services.remove(label))
+        });
+        match removed {
+            Some(_) => success(""),
+            None => failure(3, "Boot-out failed: 3: No such process"),
+        }
+    }
+
+    fn set_disabled(&mut self, target: &str, disabled: bool) -> LaunchctlResult {
+        let Some((domain, label)) = split_target(target) else {
+            return failure(64, &format!("Unrecognized target specifier: {}", target));
+        };
+        match self.domains.get_mut(domain) {
+            Some(domain) => {
+                domain.overrides.insert(label.to_string(), disabled);
+                success("")
+            }
+            None => failure(113, &format!("Could not find domain for {}", target)),
+        }
+    }
+
+    fn kickstart(&mut self, target: &str, kill: bool) -> LaunchctlResult {
+        let running = match self.service_mut(target) {
+            Some(service) => service.pid.is_some(),
+            None => return not_found(target),
+        };
+        if running && !kill {
+            return success("");
+        }
+        if let Some(service) = self.service_mut(target) {
+            if running {
+                service.last_exit_code = Some(-15);
+            }
+            service.pid = None;
+        }
+        self.start(target);
+        success("")
+    }
+
+    fn kill(&mut self, target: &str, signal: &str) -> LaunchctlResult {
+        let Some(signum) = signal_number(signal) else {
+            return failure(64, &format!("Unrecognized signal name: {}", signal));
+        };
+        let relaunch = match self.service_mut(target) {
+            Some(service) if service.pid.is_some() => {
+                service.pid = None;
+                service.last_exit_code = Some(-signum);
+                service.keep_alive
+            }
+            Some(_) => return failure(3, "Could not kill service: 3: No such process"),
+            None => return not_found(target),
+        };
+        if relaunch {
+            self.start(target);
+        }
+        success("")
+    }
+
+    fn print(&self, target: &str) -> LaunchctlResult {
+        if let Some(domain) = self.domains.get(target) {
+            return success(&render_domain(target, domain));
+        }
+        let service = split_target(target).and_then(|(domain, label)| {
+            Some((domain, self.domains.get(domain)?.services.get(label)?))
+        });
+        match service {
+            Some((domain, service)) => success(&render_service(target, domain, service)),
+            None => not_found(target),
+        }
+    }
+}
+
+/// Split `gui/501/com.foo` into (`gui/501`, `com.foo`) and `system/com.foo` into (`system`, …).
+fn split_target(target: &str) -> Option<(&str, &str)> {
+    match target.split_once('/')? {
+        ("system", label) => Some(("system", label)),
+        (_, rest) => {
+            let (_, label) = rest.split_once('/')?;
+            Some((&target[..target.len() - label.len() - 1], label))
+        }
+    }
+}
+
+fn signal_number(signal: &str) -> Option<i32> {
+    let name = signal.trim_start_matches("sig").trim_start_matches("SIG");
+    match name.to_ascii_lowercase().as_str() {
+        "hup" => Some(1),
+        "int" => Some(2),
+        "kill" => Some(9),
+        "term" => Some(15),
+        other => other.parse().ok(),
+    }
+}
+
+fn success(output: &str) -> LaunchctlResult {
+    LaunchctlResult { output: output.to_string(), exit_code: Some(0), stderr: String::new() }
+}
+
+fn failure(exit_code: i32, stderr: &str) -> LaunchctlResult {
+    LaunchctlResult {
+        output: String::new(),
+        exit_code: Some(exit_code),
+        stderr: format!("{}\n", stderr),
+    }
+}
+
+fn not_found(target: &str) -> LaunchctlResult {
+    failure(113, &format!("Could not find service \"{}\" in domain for port", target))
+}
+
+fn render_domain(name: &str, domain: &FakeDomain) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "{} = {{", name);
+    let _ = writeln!(out, "\ttype = {}", name.split('/').next().unwrap_or(name));
+    let _ = writeln!(out, "\tservice count = {}", domain.services.len());
+    let _ = writeln!(out);
+    let _ = writeln!(out, "\tservices = {{");
+    for service in domain.services.values() {
+        let pid = service.pid.map_or("-".to_string(), |p| p.to_string());
+        let status = service.last_exit_code.map_or("-".to_string(), |c| c.to_string());
+        let _ = writeln!(out, "\t\t{:>8}      {:<6}{}", pid, status, service.label);
+    }
+    let _ = writeln!(out, "\t}}");
+    let _ = writeln!(out);
+    let _ = writeln!(out, "\tdisabled services = {{");
+    for (label, disabled) in &domain.overrides {
+        let state = if *disabled { "disabled" } else { "enabled" };
+        let _ = writeln!(out, "\t\t\"{}\" => {}", label, state);
+    }
+    let _ = writeln!(out, "\t}}");
+    let _ = writeln!(out, "}}");
+    out
+}
+
+fn render_service(target: &str, domain: &str, service: &FakeService) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "{} = {{", target);
+    let _ = writeln!(out, "\tactive count = {}", u8::from(service.pid.is_some()));
+    if let Some(path) = &service.path {
+        let _ = writeln!(out, "\tpath = {}", path.display());
+    }
+    let kind = if domain == "system" { "LaunchDaemon" } else { "LaunchAgent" };
+    let _ = writeln!(out, "\ttype = {}", kind);
+    let state = if service.pid.is_some() { "running" } else { "not running" };
+    let _ = writeln!(out, "\tstate = {}", state);
+    let _ = writeln!(out);
+    if let Some(program) = &service.program {
+        let _ = writeln!(out, "\tprogram = {}", program);
+    }
+    if !service.arguments.is_empty() {
+        let _ = writeln!(out, "\targuments = {{");
+        for argument in &service.arguments {
+            let _ = writeln!(out, "\t\t{}", argument);
+        }
+        let _ = writeln!(out, "\t}}");
+        let _ = writeln!(out);
+    }
+    let _ = writeln!(out, "\tenvironment = {{");
+    for (key, value) in &service.environment {
+        let _ = writeln!(out, "\t\t{} => {}", key, value);
+    }
+    let _ = writeln!(out, "\t\tXPC_SERVICE_NAME => {}", service.label);
+    let _ = writeln!(out, "\t}}");
+    let _ = writeln!(out);
+    let _ = writeln!(out, "\tdomain = {}", domain);
+    let _ = writeln!(out, "\truns = {}", service.runs);
+    if let Some(pid) = service.pid {
+        let _ = writeln!(out, "\tpid = {}", pid);
+    }
+    let exit = service.last_exit_code.map_or("(never exited)".to_string(), |c| c.to_string());
+    let _ = writeln!(out, "\tlast exit code = {}", exit);
+    let _ = writeln!(out);
+    let mut properties = Vec::new();
+    if service.run_at_load {
+        properties.push("runatload");
+    }
+    if service.keep_alive {
+        properties.push("keepalive");
+    }
+    if service.program.is_some() && service.arguments.first() == service.program.as_ref() {
+        properties.push("inferred program");
+    }
+    if !properties.is_empty() {
+        let _ = writeln!(out, "\tproperties = {}", properties.join(" | "));
+    }
+    let _ = writeln!(out, "}}");
+    out
+}
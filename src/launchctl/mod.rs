@@ -1,10 +1,14 @@
+mod fake;
+
+pub use fake::{FakeLaunchctl, FakeService};
+
 use crate::error::{Result, SystemError};
 use crate::output;
 use crate::styles::styles;
 use crate::types::{Daemon, Domain};
 use crossterm::style::Color;
 use std::process::Command;
-use std::sync::OnceLock;
+use std::sync::{Arc, OnceLock, RwLock};
 
 #[derive(Debug)]
 pub struct LaunchctlResult {
@@ -19,6 +23,61 @@ impl LaunchctlResult {
     }
 }
 
+/// Executes a launchctl invocation. The free functions in this module build the arguments and
+/// hand them to whichever backend is installed, so everything above this layer can run without
+/// a real launchd.
+pub trait LaunchctlBackend: Send + Sync {
+    fn exec(&self, args: &[&str]) -> Result<LaunchctlResult>;
+}
+
+static BACKEND: RwLock<Option<Arc<dyn LaunchctlBackend>>> = RwLock::new(None);
+
+/// Replace the backend used by every launchctl call. Defaults to `SystemLaunchctl`.
+pub fn set_backend(backend: Arc<dyn LaunchctlBackend>) {
+    *BACKEND.write().unwrap_or_else(|e| e.into_inner()) = Some(backend);
+}
+
+fn backend() -> Arc<dyn LaunchctlBackend> {
+    BACKEND
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
+        .unwrap_or_else(|| Arc::new(SystemLaunchctl))
+}
+
+/// Spawns the real `launchctl` binary.
+pub struct SystemLaunchctl;
+
+impl LaunchctlBackend for SystemLaunchctl {
+    fn exec(&self, args: &[&str]) -> Result<LaunchctlResult> {
+        let mut args = args.to_vec();
+        let mut cmd = if is_root() {
+            args.insert(0, "launchctl");
+            Command::new("sudo")
+        } else {
+            Command::new("launchctl")
+        };
+
+        if output::is_verbose() {
+            let cmd_str = format!("{} {}", cmd.get_program().to_string_lossy(), args.join(" "));
+            output::stdout::writelogln(
+                styles::prefix(Color::Blue, "CMD"),
+                styles::command(&cmd_str),
+            );
+        }
+
+        let output = cmd.args(&args).output().map_err(|e| {
+            SystemError::Launchctl(format!("Failed to execute launchctl command: {}", e))
+        })?;
+
+        let exit_code = output.status.code();
+        let stdout_str = String::from_utf8_lossy(&output.stdout).to_string();
+        let stderr_str = String::from_utf8_lossy(&output.stderr).to_string();
+
+        Ok(LaunchctlResult { output: stdout_str, exit_code, stderr: stderr_str })
+    }
+}
+
 static ROOT_STATUS: OnceLock<bool> = OnceLock::new();
 
 fn is_root() -> bool {
@@ -98,28 +157,8 @@ pub fn print<T: PrintTarget>(target: &T) -> Result<LaunchctlResult> {
     launchctl_exec(vec!["print", &target.target_str()])
 }
 
-fn launchctl_exec(mut args: Vec<&str>) -> Result<LaunchctlResult> {
-    let mut cmd = if is_root() {
-        args.insert(0, "launchctl");
-        Command::new("sudo")
-    } else {
-        Command::new("launchctl")
-    };
-
-    if output::is_verbose() {
-        let cmd_str = format!("{} {}", cmd.get_program().to_string_lossy(), args.join(" "));
-        output::stdout::writelogln(styles::prefix(Color::Blue, "CMD"), styles::command(&cmd_str));
-    }
-
-    let output = cmd.args(&args).output().map_err(|e| {
-        SystemError::Launchctl(format!("Failed to execute launchctl command: {}", e))
-    })?;
-
-    let exit_code = output.status.code();
-    let stdout_str = String::from_utf8_lossy(&output.stdout).to_string();
-    let stderr_str = String::from_utf8_lossy(&output.stderr).to_string();
-
-    Ok(LaunchctlResult { output: stdout_str, exit_code, stderr: stderr_str })
+fn launchctl_exec(args: Vec<&str>) -> Result<LaunchctlResult> {
+    backend().exec(&args)
 }
 
 fn service_target(daemon: &Daemon) -> String {
@@ -3,10 +3,15 @@ pub mod cli;
 pub mod commands;
 pub mod config;
 pub mod daemon;
-pub mod styles;
 pub mod error;
 pub mod launchctl;
+pub mod manifest;
 pub mod output;
+pub mod overrides;
 pub mod parser;
+pub mod paths;
+pub mod prompt;
+pub mod schedule;
+pub mod styles;
 pub mod types;
-
+pub mod watch;
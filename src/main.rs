@@ -1,27 +1,21 @@
-mod angel;
-mod cli;
-mod commands;
-mod config;
-mod daemon;
-mod styles;
-mod error;
-mod launchctl;
-mod output;
-mod parser;
-mod types;
-
-use clap::Parser;
-use cli::{Cli, Commands};
-use error::AngelError;
+use angel::cli::Cli;
+use angel::error::AngelError;
+use angel::{commands, output, prompt};
+use clap::{CommandFactory, Parser};
+use clap_complete::CompleteEnv;
 
 fn main() {
+    // Answers the completion scripts' callbacks and exits, before anything is printed
+    CompleteEnv::with_factory(Cli::command).var(commands::completions::COMPLETE_ENV).complete();
+
     let cli = Cli::parse();
 
     // Initialize output context before any commands run
-    output::init(cli.verbose);
+    output::init(cli.verbose, cli.format);
+    prompt::init(cli.yes, cli.no_input);
 
     // Load Angel instance before any command runs
-    let angel = match angel::Angel::load() {
+    let angel = match angel::angel::Angel::load() {
         Ok(angel) => angel,
         Err(e) => {
             match e {
@@ -32,25 +26,7 @@ fn main() {
         }
     };
 
-    let result = match cli.command {
-        Commands::Start(args) => commands::start::run(&angel, &args),
-        Commands::Stop(args) => commands::stop::run(&angel, &args),
-        Commands::Restart(args) => commands::restart::run(&angel, &args),
-        Commands::Status(args) => commands::status::run(&angel, &args),
-        Commands::List(args) => commands::list::run(&angel, &args),
-        Commands::Plist(args) => commands::show::run(&angel, &args),
-        Commands::Install(args) => commands::install::run(&angel, &args),
-        Commands::Uninstall(args) => commands::uninstall::run(&angel, &args),
-        Commands::Bootstrap(args) => commands::bootstrap::run(&angel, &args),
-        Commands::Bootout(args) => commands::bootout::run(&angel, &args),
-        Commands::Enable(args) => commands::enable::run(&angel, &args),
-        Commands::Disable(args) => commands::disable::run(&angel, &args),
-        Commands::Print(args) => commands::print::run(&angel, &args),
-        Commands::Version => {
-            commands::version::run();
-            Ok(())
-        }
-    };
+    let result = commands::run(&angel, cli.command);
 
     if let Err(e) = result {
         match e {
@@ -0,0 +1,163 @@
+//! Declarative service definitions for `angel apply`.
+//!
+//! A manifest is YAML, like `.angelrc`:
+//!
+//! ```yaml
+//! services:
+//!   - label: com.mycorp.sync
+//!     domain: gui
+//!     program: /usr/local/bin/sync
+//!     args: [--all]
+//!     env: { RUST_LOG: info }
+//!     run_at_load: true
+//!     calendar: ["Hour=9,Minute=0", "30 2 * * 1-5"]
+//!     plist: { ThrottleInterval: 30 }
+//! ```
+
+use crate::error::{Result, UserError};
+use crate::schedule;
+use crate::types::{CalendarInterval, DomainKind, KeepAlive, Plist};
+use serde::{Deserialize, Deserializer};
+use std::collections::{BTreeMap, HashSet};
+use std::path::Path;
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Manifest {
+    #[serde(default)]
+    pub services: Vec<ServiceSpec>,
+}
+
+/// One service, in terms of the handful of keys most jobs need. Anything else goes in
+/// `plist`, which is merged over the generated keys verbatim.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ServiceSpec {
+    pub label: String,
+    #[serde(default)]
+    pub domain: Option<DomainKind>,
+    pub program: Option<String>,
+    #[serde(default)]
+    pub args: Vec<String>,
+    #[serde(default)]
+    pub env: BTreeMap<String, String>,
+    pub working_directory: Option<String>,
+    pub stdout: Option<String>,
+    pub stderr: Option<String>,
+    #[serde(default)]
+    pub run_at_load: bool,
+    #[serde(default)]
+    pub keep_alive: bool,
+    pub interval: Option<i32>,
+    #[serde(default, deserialize_with = "deserialize_calendar")]
+    pub calendar: Vec<CalendarInterval>,
+    #[serde(default)]
+    pub plist: BTreeMap<String, plist::Value>,
+}
+
+impl Manifest {
+    pub fn load(path: &Path) -> Result<Manifest> {
+        let content = std::fs::read_to_string(path)?;
+        let manifest: Manifest = serde_yaml::from_str(&content)
+            .map_err(|e| UserError::InvalidArgument(format!("{}: {}", path.display(), e)))?;
+
+        let mut seen = HashSet::new();
+        for service in &manifest.services {
+            if !seen.insert((service.domain(), service.label.as_str())) {
+                return Err(UserError::InvalidArgument(format!(
+                    "{}: {} is defined twice",
+                    path.display(),
+                    service.label
+                ))
+                .into());
+            }
+        }
+        Ok(manifest)
+    }
+}
+
+impl ServiceSpec {
+    /// Services go to the GUI domain unless the manifest says otherwise.
+    pub fn domain(&self) -> DomainKind {
+        self.domain.unwrap_or(DomainKind::Gui)
+    }
+
+    pub fn to_plist(&self) -> Result<Plist> {
+        // launchd passes ProgramArguments as argv, so argv[0] has to be the program itself
+        let program_arguments = match (&self.program, self.args.is_empty()) {
+            (_, true) => None,
+            (Some(program), false) => {
+                Some(std::iter::once(program.clone()).chain(self.args.iter().cloned()).collect())
+            }
+            (None, false) => Some(self.args.clone()),
+        };
+        if self.program.is_none() && program_arguments.is_none() {
+            return Err(UserError::InvalidArgument(format!(
+                "{} needs a program or args",
+                self.label
+            ))
+            .into());
+        }
+
+        let plist = Plist {
+            label: Some(self.label.clone()),
+            program: self.program.clone(),
+            program_arguments,
+            environment_variables: Some(self.env.clone()).filter(|env| !env.is_empty()),
+            run_at_load: self.run_at_load.then_some(true),
+            keep_alive: self.keep_alive.then_some(KeepAlive::Always(true)),
+            start_interval: self.interval,
+            start_calendar_interval: match self.calendar.is_empty() {
+                true => None,
+                false => Some(self.calendar.clone().into()),
+            },
+            standard_out_path: self.stdout.clone(),
+            standard_error_path: self.stderr.clone(),
+            working_directory: self.working_directory.clone(),
+            ..Default::default()
+        };
+        if self.plist.is_empty() {
+            return Ok(plist);
+        }
+
+        // Merge raw keys over the typed ones, then re-read so known keys land in their fields
+        let mut dict = match plist::to_value(&plist)? {
+            plist::Value::Dictionary(dict) => dict,
+            _ => plist::Dictionary::new(),
+        };
+        for (key, value) in &self.plist {
+            dict.insert(key.clone(), value.clone());
+        }
+        Ok(plist::from_value(&plist::Value::Dictionary(dict))?)
+    }
+}
+
+/// Calendar entries may be written as `Hour=9,Minute=0`, as a `{Hour: 9, Minute: 0}` map, or
+/// as a cron expression, which can expand to several entries.
+fn deserialize_calendar<'de, D>(
+    deserializer: D,
+) -> std::result::Result<Vec<CalendarInterval>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Entry {
+        Text(String),
+        Map(CalendarInterval),
+    }
+
+    let mut intervals = Vec::new();
+    for entry in Vec::<Entry>::deserialize(deserializer)? {
+        match entry {
+            Entry::Text(text) if text.contains('=') => {
+                intervals.push(text.parse().map_err(serde::de::Error::custom)?)
+            }
+            Entry::Text(text) => {
+                intervals.extend(schedule::parse_cron(&text).map_err(serde::de::Error::custom)?)
+            }
+            Entry::Map(interval) => intervals.push(interval),
+        }
+    }
+    Ok(intervals)
+}
@@ -1,23 +1,38 @@
+use crate::error::{Result, SystemError};
+use clap::ValueEnum;
 use crossterm::style::Color;
+use serde::Serialize;
 use std::io::{self, Write};
 use std::sync::OnceLock;
 
+/// How commands that produce data (`list`, `status`, `print`, `plist`) render it
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// Human-readable tables and text (default)
+    #[default]
+    Table,
+    Json,
+    Yaml,
+    Plist,
+}
+
 /// Global output context storing configuration
 pub struct OutputContext {
     pub verbose: bool,
+    pub format: OutputFormat,
 }
 
 static CONTEXT: OnceLock<OutputContext> = OnceLock::new();
 
-/// Initialize the output context with the verbose flag
+/// Initialize the output context with the verbose flag and output format
 /// Must be called before any output functions are used
-pub fn init(verbose: bool) {
-    CONTEXT.set(OutputContext { verbose }).ok();
+pub fn init(verbose: bool, format: OutputFormat) {
+    CONTEXT.set(OutputContext { verbose, format }).ok();
 }
 
 #[inline(always)]
 fn context() -> &'static OutputContext {
-    CONTEXT.get_or_init(|| OutputContext { verbose: false })
+    CONTEXT.get_or_init(|| OutputContext { verbose: false, format: OutputFormat::Table })
 }
 
 /// Check if verbose output is enabled
@@ -26,6 +41,32 @@ pub fn is_verbose() -> bool {
     context().verbose
 }
 
+/// Check if data should be emitted as JSON, YAML or plist instead of tables
+#[inline(always)]
+pub fn is_structured() -> bool {
+    context().format != OutputFormat::Table
+}
+
+/// Write `value` to stdout in the selected structured format
+pub fn emit<T: Serialize>(value: &T) -> Result<()> {
+    let serialized = match context().format {
+        OutputFormat::Json => serde_json::to_string_pretty(value)
+            .map_err(|e| SystemError::Serialize(e.to_string()))?,
+        // YAML has no byte strings, so go through JSON's representation of plist <data>
+        OutputFormat::Yaml => serde_json::to_value(value)
+            .map_err(|e| e.to_string())
+            .and_then(|json| serde_yaml::to_string(&json).map_err(|e| e.to_string()))
+            .map_err(SystemError::Serialize)?,
+        OutputFormat::Plist | OutputFormat::Table => {
+            let mut buf = Vec::new();
+            plist::to_writer_xml(&mut buf, value)?;
+            String::from_utf8_lossy(&buf).to_string()
+        }
+    };
+    stdout::writeln(serialized.trim_end());
+    Ok(())
+}
+
 macro_rules! write_to_stream {
     (io::$stream:ident, $msg:expr, $newline:expr) => {
         let result = match $newline {
@@ -68,6 +109,11 @@ pub mod stdout {
         writelogln(prefix(Color::Red, "✘"), msg);
     }
 
+    #[inline(always)]
+    pub fn warn(msg: &str) {
+        writelogln(prefix(Color::Yellow, "!"), msg);
+    }
+
     #[inline(always)]
     pub fn hint(msg: &str) {
         writelogln(prefix(Color::Yellow, "→"), msg);
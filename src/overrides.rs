@@ -0,0 +1,267 @@
+//! launchd's enable/disable override database, under `/var/db/com.apple.xpc.launchd`:
+//! `disabled.plist` for the system domain and `disabled.<uid>.plist` for each user's `gui/<uid>`
+//! and `user/<uid>` domains. Each file maps a label to whether it is disabled.
+//!
+//! launchd reads these files at boot and otherwise works from its own copy, which it writes
+//! back whenever `launchctl enable` or `disable` runs. Edits made here therefore only stick
+//! until then, or take effect at the next reboot.
+
+use crate::error::{AngelError, Result, UserError};
+use crate::paths::Paths;
+use crate::types::Domain;
+use nix::fcntl::{Flock, FlockArg};
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+
+/// One `disabled*.plist` file.
+#[derive(Debug, Clone)]
+pub struct OverrideDb {
+    /// `None` for the system database
+    pub uid: Option<u32>,
+    pub path: PathBuf,
+    /// label -> disabled
+    pub overrides: BTreeMap<String, bool>,
+}
+
+impl OverrideDb {
+    /// The database `domain`'s overrides live in. Empty when the file doesn't exist yet.
+    pub fn for_domain(paths: &Paths, domain: &Domain) -> Result<Self> {
+        let uid = match domain {
+            Domain::User(uid) | Domain::Gui(uid) => Some(*uid),
+            Domain::System | Domain::Unknown => None,
+        };
+        Self::load(paths.disabled_overrides(domain), uid)
+    }
+
+    fn load(path: PathBuf, uid: Option<u32>) -> Result<Self> {
+        let overrides = match fs::read(&path) {
+            Ok(bytes) => plist::from_bytes(&bytes)?,
+            Err(e) if e.kind() == ErrorKind::NotFound => BTreeMap::new(),
+            Err(e) => return Err(io_error(e)),
+        };
+        Ok(Self { uid, path, overrides })
+    }
+
+    /// `system`, or `uid <uid>` for a per-user database.
+    pub fn scope(&self) -> String {
+        match self.uid {
+            Some(uid) => format!("uid {}", uid),
+            None => "system".to_string(),
+        }
+    }
+
+    /// The domains this database holds overrides for.
+    pub fn domains(&self) -> Vec<Domain> {
+        match self.uid {
+            Some(uid) => vec![Domain::Gui(uid), Domain::User(uid)],
+            None => vec![Domain::System],
+        }
+    }
+
+    /// Drop `label`'s override. The file is re-read under the lock first, so an edit made since
+    /// `self` was loaded isn't lost. Returns the backup of the previous contents, or `None` when
+    /// there was no override to clear.
+    pub fn clear(&mut self, label: &str) -> Result<Option<PathBuf>> {
+        let _lock = lock(&self.path)?;
+        *self = Self::load(self.path.clone(), self.uid)?;
+        if self.overrides.remove(label).is_none() {
+            return Ok(None);
+        }
+        self.write().map(Some)
+    }
+
+    /// Back the current file up to `<name>.<timestamp>.bak`, write the new contents to a
+    /// temporary file beside it, and rename that into place, so launchd never sees a partial
+    /// file. Callers hold the lock.
+    fn write(&self) -> Result<PathBuf> {
+        let dir = self.path.parent().unwrap_or(Path::new("."));
+        let name = self.path.file_name().unwrap_or_default().to_string_lossy();
+        let backup = backup_path(dir, &name);
+        fs::copy(&self.path, &backup).map_err(io_error)?;
+
+        let temp = dir.join(format!(".{}.{}.tmp", name, std::process::id()));
+        let written = (|| -> Result<()> {
+            let mut file = File::create(&temp)?;
+            plist::to_writer_xml(&mut file, &self.overrides)?;
+            file.flush()?;
+            file.sync_all()?;
+            fs::set_permissions(&temp, fs::metadata(&self.path)?.permissions())?;
+            fs::rename(&temp, &self.path)?;
+            Ok(())
+        })();
+        if written.is_err() {
+            let _ = fs::remove_file(&temp);
+        }
+        written.map(|_| backup)
+    }
+}
+
+/// `<name>.<timestamp>.bak`, or `<name>.<timestamp>-<n>.bak` when a backup was already made
+/// within the same second. Callers hold the lock, so nothing else takes the name meanwhile.
+fn backup_path(dir: &Path, name: &str) -> PathBuf {
+    let stamp = jiff::Zoned::now().strftime("%Y%m%dT%H%M%S").to_string();
+    let mut backup = dir.join(format!("{}.{}.bak", name, stamp));
+    let mut n = 0;
+    while backup.exists() {
+        n += 1;
+        backup = dir.join(format!("{}.{}-{}.bak", name, stamp, n));
+    }
+    backup
+}
+
+/// Every database in launchd's directory: the system one first, then per-user ones by uid.
+/// Files that can't be read are skipped and returned as errors alongside, so one unreadable
+/// file doesn't hide the rest.
+pub fn load_all(paths: &Paths) -> (Vec<OverrideDb>, Vec<AngelError>) {
+    let Ok(entries) = fs::read_dir(paths.launchd_db()) else { return (Vec::new(), Vec::new()) };
+
+    let mut files: Vec<(Option<u32>, PathBuf)> = entries
+        .filter_map(|entry| {
+            let path = entry.ok()?.path();
+            let name = path.file_name()?.to_str()?;
+            let scope = name.strip_prefix("disabled")?.strip_suffix(".plist")?;
+            let uid = match scope {
+                "" => None,
+                _ => Some(scope.strip_prefix('.')?.parse().ok()?),
+            };
+            Some((uid, path))
+        })
+        .collect();
+    files.sort();
+
+    let mut databases = Vec::new();
+    let mut errors = Vec::new();
+    for (uid, path) in files {
+        match OverrideDb::load(path, uid) {
+            Ok(db) => databases.push(db),
+            Err(e) => errors.push(e),
+        }
+    }
+    (databases, errors)
+}
+
+/// Exclusive lock on the database's directory, held until dropped. The file itself gets
+/// replaced by each write, so locking it would let two writers hold locks on different files.
+fn lock(path: &Path) -> Result<Flock<File>> {
+    let dir = File::open(path.parent().unwrap_or(Path::new("."))).map_err(io_error)?;
+    Flock::lock(dir, FlockArg::LockExclusive).map_err(|(_, errno)| errno.into())
+}
+
+/// launchd's directory is root-only, so a permission error there means sudo is needed.
+fn io_error(e: std::io::Error) -> AngelError {
+    match e.kind() {
+        ErrorKind::PermissionDenied => UserError::RequiresRoot.into(),
+        _ => e.into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A scratch root holding launchd's directory, removed when dropped.
+    struct Scratch {
+        root: PathBuf,
+        paths: Paths,
+    }
+
+    impl Scratch {
+        fn new(name: &str) -> Self {
+            let root = std::env::temp_dir().join(format!(
+                "angel-overrides-{}-{}",
+                std::process::id(),
+                name
+            ));
+            let _ = fs::remove_dir_all(&root);
+            let paths = Paths::new(&root, None);
+            fs::create_dir_all(paths.launchd_db()).unwrap();
+            Self { root, paths }
+        }
+
+        /// Write the system database with `overrides`, returning its path.
+        fn write(&self, overrides: &[(&str, bool)]) -> PathBuf {
+            let path = self.paths.disabled_overrides(&Domain::System);
+            let overrides: BTreeMap<&str, bool> = overrides.iter().copied().collect();
+            plist::to_file_xml(&path, &overrides).unwrap();
+            path
+        }
+
+        fn files(&self) -> Vec<String> {
+            let mut names: Vec<String> = fs::read_dir(self.paths.launchd_db())
+                .unwrap()
+                .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+                .collect();
+            names.sort();
+            names
+        }
+    }
+
+    impl Drop for Scratch {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.root);
+        }
+    }
+
+    fn read(path: &Path) -> BTreeMap<String, bool> {
+        plist::from_file(path).unwrap()
+    }
+
+    #[test]
+    fn clear_removes_only_the_named_label() {
+        let scratch = Scratch::new("clear");
+        let path = scratch.write(&[("com.example.a", true), ("com.example.b", false)]);
+        let before = fs::read(&path).unwrap();
+
+        let mut db = OverrideDb::for_domain(&scratch.paths, &Domain::System).unwrap();
+        let backup = db.clear("com.example.a").unwrap().expect("a backup");
+
+        let expected = BTreeMap::from([("com.example.b".to_string(), false)]);
+        assert_eq!(db.overrides, expected);
+        assert_eq!(read(&path), expected);
+        assert_eq!(fs::read(&backup).unwrap(), before);
+        // Only the database and its backup, no temporary file left behind
+        assert_eq!(scratch.files().len(), 2);
+        assert!(scratch.files().iter().all(|name| !name.ends_with(".tmp")));
+    }
+
+    #[test]
+    fn clear_rereads_the_file_first() {
+        let scratch = Scratch::new("reread");
+        let path = scratch.write(&[("com.example.a", true)]);
+        let mut db = OverrideDb::for_domain(&scratch.paths, &Domain::System).unwrap();
+        // launchd wrote the file since it was loaded
+        scratch.write(&[("com.example.a", true), ("com.example.new", true)]);
+
+        db.clear("com.example.a").unwrap();
+        assert_eq!(read(&path), BTreeMap::from([("com.example.new".to_string(), true)]));
+    }
+
+    #[test]
+    fn clear_without_an_override_writes_nothing() {
+        let scratch = Scratch::new("absent");
+        let path = scratch.write(&[("com.example.a", true)]);
+        let before = fs::read(&path).unwrap();
+
+        let mut db = OverrideDb::for_domain(&scratch.paths, &Domain::System).unwrap();
+        assert_eq!(db.clear("com.example.missing").unwrap(), None);
+        assert_eq!(fs::read(&path).unwrap(), before);
+        assert_eq!(scratch.files(), ["disabled.plist"]);
+    }
+
+    #[test]
+    fn backups_within_a_second_keep_each_version() {
+        let scratch = Scratch::new("backups");
+        scratch.write(&[("com.example.a", true), ("com.example.b", true), ("com.example.c", true)]);
+        let mut db = OverrideDb::for_domain(&scratch.paths, &Domain::System).unwrap();
+
+        let backups: Vec<PathBuf> = ["com.example.a", "com.example.b", "com.example.c"]
+            .iter()
+            .map(|label| db.clear(label).unwrap().unwrap())
+            .collect();
+        let sizes: Vec<usize> = backups.iter().map(|backup| read(backup).len()).collect();
+        assert_eq!(sizes, [3, 2, 1]);
+        assert!(db.overrides.is_empty());
+    }
+}
@@ -1,117 +1,668 @@
 use crate::error::Result;
 use crate::launchctl;
 use crate::output;
-use crate::types::{Daemon, Domain};
+use crate::types::{
+    Daemon, Domain, DomainInfo, DomainService, Endpoint, EventTrigger, ServiceInfo, ServiceState,
+    UnmanagedProcess,
+};
+use std::collections::BTreeMap;
 use std::path::PathBuf;
 
 pub struct Parser;
 
 impl Parser {
-    pub fn parse_print_domain(
-        domain: &Domain,
-    ) -> Result<Vec<(Option<u32>, Option<String>, String)>> {
+    pub fn parse_print_domain(domain: &Domain) -> Result<Option<DomainInfo>> {
         let result = launchctl::print(&domain.to_string())?;
 
         if !result.success() {
-            return Ok(Vec::new());
+            return Ok(None);
+        }
+
+        Ok(Self::parse_domain_info(&result.output))
+    }
+
+    /// Parse the output of `launchctl print <domain>`.
+    pub fn parse_domain_info(output: &str) -> Option<DomainInfo> {
+        let (name, root) = Self::parse_tree(output)?;
+        let mut info = DomainInfo { name, ..Default::default() };
+
+        for entry in &root.entries {
+            let PrintEntry::Field(key, value) = entry else { continue };
+            match (key.as_str(), value) {
+                ("type", PrintValue::Scalar(v)) => info.domain_type = Some(v.clone()),
+                ("services", PrintValue::Block(b)) => info.services = parse_services(b),
+                ("disabled services", PrintValue::Block(b)) => {
+                    info.disabled_services = parse_disabled_services(b)
+                }
+                ("endpoints", PrintValue::Block(b)) => info.endpoints = parse_endpoints(b),
+                ("unmanaged processes", PrintValue::Block(b)) => {
+                    info.unmanaged_processes = parse_unmanaged_processes(b)
+                }
+                ("subdomains", PrintValue::Block(b)) => info.subdomains = b.lines(),
+                ("properties", PrintValue::Scalar(v)) => info.properties = split_properties(v),
+                (key, PrintValue::Scalar(v)) => {
+                    info.other.insert(key.to_string(), v.clone());
+                }
+                _ => {}
+            }
         }
 
-        let output = &result.output;
+        Some(info)
+    }
+
+    pub fn parse_print_service(daemon: &Daemon) -> Result<Option<ServiceInfo>> {
+        let result = launchctl::print(daemon)?;
+
+        if !result.success() {
+            return Ok(None);
+        }
 
-        // Find the services section
-        let services_start = match output.find("services = {") {
-            Some(pos) => pos + 13,
-            None => return Ok(Vec::new()),
+        Ok(Self::parse_service_info(&result.output))
+    }
+
+    /// Parse the output of `launchctl print <domain>/<label>`.
+    pub fn parse_service_info(output: &str) -> Option<ServiceInfo> {
+        let (target, root) = Self::parse_tree(output)?;
+        if target.is_empty() {
+            return None;
+        }
+
+        let mut info = ServiceInfo {
+            target,
+            path: None,
+            service_type: None,
+            state: ServiceState::Other("unknown".to_string()),
+            program: None,
+            arguments: Vec::new(),
+            working_directory: None,
+            stdout_path: None,
+            stderr_path: None,
+            inherited_environment: BTreeMap::new(),
+            default_environment: BTreeMap::new(),
+            environment: BTreeMap::new(),
+            domain: None,
+            pid: None,
+            runs: None,
+            last_exit_code: None,
+            last_terminating_signal: None,
+            spawn_type: None,
+            run_interval: None,
+            properties: Vec::new(),
+            endpoints: Vec::new(),
+            event_triggers: Vec::new(),
+            other: BTreeMap::new(),
         };
-        let services_end = match output[services_start..].find('}') {
-            Some(pos) => services_start + pos - 1,
-            None => output.len(),
+
+        for entry in &root.entries {
+            let PrintEntry::Field(key, value) = entry else { continue };
+            match (key.as_str(), value) {
+                ("path", PrintValue::Scalar(v)) => info.path = Some(PathBuf::from(v)),
+                ("type", PrintValue::Scalar(v)) => info.service_type = Some(v.clone()),
+                ("state", PrintValue::Scalar(v)) => info.state = ServiceState::parse(v),
+                ("program", PrintValue::Scalar(v)) => info.program = Some(v.clone()),
+                ("arguments", PrintValue::Block(b)) => info.arguments = b.lines(),
+                ("working directory", PrintValue::Scalar(v)) => {
+                    info.working_directory = Some(v.clone())
+                }
+                ("stdout path", PrintValue::Scalar(v)) => info.stdout_path = Some(v.clone()),
+                ("stderr path", PrintValue::Scalar(v)) => info.stderr_path = Some(v.clone()),
+                ("inherited environment", PrintValue::Block(b)) => {
+                    info.inherited_environment = b.to_map()
+                }
+                ("default environment", PrintValue::Block(b)) => {
+                    info.default_environment = b.to_map()
+                }
+                ("environment", PrintValue::Block(b)) => info.environment = b.to_map(),
+                // "gui/501 [100005]" -> "gui/501"
+                ("domain", PrintValue::Scalar(v)) => {
+                    info.domain = v.split_whitespace().next().map(str::to_string)
+                }
+                ("pid", PrintValue::Scalar(v)) => info.pid = v.parse().ok(),
+                ("runs", PrintValue::Scalar(v)) => info.runs = v.parse().ok(),
+                ("last exit code", PrintValue::Scalar(v)) => info.last_exit_code = Some(v.clone()),
+                ("last terminating signal", PrintValue::Scalar(v)) => {
+                    info.last_terminating_signal = Some(v.clone())
+                }
+                ("spawn type", PrintValue::Scalar(v)) => info.spawn_type = Some(v.clone()),
+                ("run interval", PrintValue::Scalar(v)) => info.run_interval = Some(v.clone()),
+                ("properties", PrintValue::Scalar(v)) => info.properties = split_properties(v),
+                ("endpoints", PrintValue::Block(b)) => info.endpoints = parse_endpoints(b),
+                ("event triggers", PrintValue::Block(b)) => {
+                    info.event_triggers = parse_event_triggers(b)
+                }
+                (key, PrintValue::Scalar(v)) => {
+                    info.other.insert(key.to_string(), v.clone());
+                }
+                _ => {}
+            }
+        }
+
+        Some(info)
+    }
+
+    /// Parse any `launchctl print` output into its header name and nested body.
+    ///
+    /// The format is line based: `key = value` and `key => value` are fields, a trailing `{`
+    /// opens a nested block that runs until a lone `}`, and anything else is a bare item (an
+    /// argument, a `services` row, …). Unterminated blocks end at the end of the input.
+    pub fn parse_tree(output: &str) -> Option<(String, PrintBlock)> {
+        let mut lines = output.lines();
+        let header = lines.by_ref().map(str::trim).find(|line| !line.is_empty())?;
+        let name = header.strip_suffix('{')?.trim_end().strip_suffix('=')?.trim();
+        Some((unquote(name).to_string(), parse_block(&mut lines)))
+    }
+}
+
+/// One node of `launchctl print` output.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PrintValue {
+    Scalar(String),
+    Block(PrintBlock),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PrintEntry {
+    /// `key = value`, `key => value`, or `key = { … }`
+    Field(String, PrintValue),
+    /// A bare line such as a program argument
+    Item(String),
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PrintBlock {
+    pub entries: Vec<PrintEntry>,
+}
+
+impl PrintBlock {
+    pub fn get(&self, key: &str) -> Option<&PrintValue> {
+        self.entries.iter().find_map(|entry| match entry {
+            PrintEntry::Field(k, value) if k == key => Some(value),
+            _ => None,
+        })
+    }
+
+    pub fn scalar(&self, key: &str) -> Option<&str> {
+        match self.get(key)? {
+            PrintValue::Scalar(value) => Some(value),
+            PrintValue::Block(_) => None,
+        }
+    }
+
+    pub fn block(&self, key: &str) -> Option<&PrintBlock> {
+        match self.get(key)? {
+            PrintValue::Block(block) => Some(block),
+            PrintValue::Scalar(_) => None,
+        }
+    }
+
+    /// Bare items in order. Scalar fields are rebuilt as `key = value` so that list blocks
+    /// keep entries that happen to contain an `=`.
+    pub fn lines(&self) -> Vec<String> {
+        self.entries
+            .iter()
+            .filter_map(|entry| match entry {
+                PrintEntry::Item(item) => Some(item.clone()),
+                PrintEntry::Field(key, PrintValue::Scalar(value)) => {
+                    Some(format!("{} = {}", key, value))
+                }
+                PrintEntry::Field(_, PrintValue::Block(_)) => None,
+            })
+            .collect()
+    }
+
+    /// Scalar fields as a map, e.g. an `environment` block.
+    pub fn to_map(&self) -> BTreeMap<String, String> {
+        self.entries
+            .iter()
+            .filter_map(|entry| match entry {
+                PrintEntry::Field(key, PrintValue::Scalar(value)) => {
+                    Some((key.clone(), value.clone()))
+                }
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+fn parse_block<'a>(lines: &mut impl Iterator<Item = &'a str>) -> PrintBlock {
+    let mut block = PrintBlock::default();
+    while let Some(line) = lines.next() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        if line == "}" {
+            break;
+        }
+        let entry = match split_field(line) {
+            Some((key, "{")) => PrintEntry::Field(key, PrintValue::Block(parse_block(lines))),
+            Some((key, value)) => PrintEntry::Field(key, PrintValue::Scalar(unquote(value).into())),
+            None => match line.strip_suffix('{') {
+                Some(key) => PrintEntry::Field(
+                    unquote(key.trim()).into(),
+                    PrintValue::Block(parse_block(lines)),
+                ),
+                None => PrintEntry::Item(line.to_string()),
+            },
         };
+        block.entries.push(entry);
+    }
+    block
+}
 
-        let services_section = &output[services_start..services_end];
-        let mut services = Vec::new();
+/// Split a line at its first ` = ` or ` => ` separator, skipping over a quoted key.
+fn split_field(line: &str) -> Option<(String, &str)> {
+    let key_end = match line.strip_prefix('"') {
+        Some(rest) => rest.find('"').map_or(0, |i| i + 2),
+        None => 0,
+    };
+    let rest = &line[key_end..];
+    let (pos, len) = match (rest.find(" = "), rest.find(" => ")) {
+        (Some(eq), Some(arrow)) if arrow < eq => (arrow, 4),
+        (Some(eq), _) => (eq, 3),
+        (None, Some(arrow)) => (arrow, 4),
+        (None, None) => match (rest.strip_suffix(" ="), rest.strip_suffix(" =>")) {
+            (Some(key), _) | (None, Some(key)) => {
+                return Some((unquote(&line[..key_end + key.len()]).into(), ""));
+            }
+            (None, None) => return None,
+        },
+    };
+    let key = unquote(line[..key_end + pos].trim()).to_string();
+    Some((key, line[key_end + pos + len..].trim()))
+}
 
-        for line in services_section.lines() {
-            let trimmed = line.trim();
+fn split_properties(properties: &str) -> Vec<String> {
+    properties.split('|').map(|p| p.trim().to_string()).filter(|p| !p.is_empty()).collect()
+}
 
-            // Split by whitespace
-            let parts: Vec<&str> = trimmed.split_whitespace().collect();
+/// `services` rows are `pid  last-exit-code  label`, with `-` for "none". Anything shorter is
+/// skipped, and only mentioned with `--verbose`.
+fn parse_services(block: &PrintBlock) -> Vec<DomainService> {
+    let mut services = Vec::new();
+    for line in block.lines() {
+        let parts: Vec<&str> = line.split_whitespace().collect();
 
-            if parts.len() < 3 {
+        if parts.len() < 3 {
+            if output::is_verbose() {
                 output::stderr::warn(&format!(
                     "Warning: launchctl output format has changed - skipping line: {}",
                     line
                 ));
-                continue;
             }
+            continue;
+        }
 
-            let pid = parts[0].parse::<u32>().ok();
-            let exit_code = Some(parts[1].to_string());
-            let name = parts[2..].join(" ").trim().to_string();
+        services.push(DomainService {
+            pid: parts[0].parse::<u32>().ok(),
+            last_exit_code: Some(parts[1].to_string()),
+            label: parts[2..].join(" "),
+        });
+    }
+    services
+}
+
+/// Newer releases print `"label" => disabled|enabled`, older ones `"label" => true|false`.
+fn parse_disabled_services(block: &PrintBlock) -> BTreeMap<String, bool> {
+    block
+        .to_map()
+        .into_iter()
+        .filter_map(|(label, state)| match state.as_str() {
+            "disabled" | "true" => Some((label, true)),
+            "enabled" | "false" => Some((label, false)),
+            _ => None,
+        })
+        .collect()
+}
+
+fn parse_unmanaged_processes(block: &PrintBlock) -> Vec<UnmanagedProcess> {
+    block
+        .entries
+        .iter()
+        .filter_map(|entry| match entry {
+            PrintEntry::Field(name, PrintValue::Block(fields)) => Some(UnmanagedProcess {
+                name: name.clone(),
+                endpoints: fields
+                    .block("dynamic endpoints")
+                    .map(parse_endpoints)
+                    .unwrap_or_default(),
+                attributes: fields.to_map(),
+            }),
+            PrintEntry::Item(name) => {
+                Some(UnmanagedProcess { name: name.clone(), ..Default::default() })
+            }
+            _ => None,
+        })
+        .collect()
+}
 
-            if !name.is_empty() {
-                services.push((pid, exit_code, name));
+fn unquote(s: &str) -> &str {
+    s.strip_prefix('"').and_then(|s| s.strip_suffix('"')).unwrap_or(s)
+}
+
+/// Endpoints appear either as `"name" = { port = …; active = … }` blocks or, in domain
+/// output, as `0x1e203    M   A   com.apple.foo` rows.
+fn parse_endpoints(block: &PrintBlock) -> Vec<Endpoint> {
+    block
+        .entries
+        .iter()
+        .filter_map(|entry| match entry {
+            PrintEntry::Field(name, PrintValue::Block(fields)) => {
+                let mut attributes = fields.to_map();
+                let port = attributes.remove("port");
+                let active = attributes.remove("active").is_some_and(|v| v == "1");
+                let managed = attributes.remove("managed").is_some_and(|v| v == "1");
+                Some(Endpoint { name: name.clone(), port, active, managed, attributes })
             }
+            PrintEntry::Item(row) => {
+                let parts: Vec<&str> = row.split_whitespace().collect();
+                let (port, rest) = parts.split_first()?;
+                let (name, flags) = rest.split_last()?;
+                Some(Endpoint {
+                    name: name.to_string(),
+                    port: Some(port.to_string()),
+                    active: flags.contains(&"A"),
+                    managed: flags.contains(&"M"),
+                    attributes: BTreeMap::new(),
+                })
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+fn parse_event_triggers(block: &PrintBlock) -> Vec<EventTrigger> {
+    block
+        .entries
+        .iter()
+        .filter_map(|entry| match entry {
+            PrintEntry::Field(name, PrintValue::Block(fields)) => {
+                let mut attributes = fields.to_map();
+                Some(EventTrigger {
+                    name: name.clone(),
+                    stream: attributes.remove("stream"),
+                    keepalive: attributes.remove("keepalive").is_some_and(|v| v == "1"),
+                    descriptor: fields
+                        .block("descriptor")
+                        .map(PrintBlock::to_map)
+                        .unwrap_or_default(),
+                    attributes,
+                })
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SONOMA_GUI_SERVICE: &str =
+        include_str!("../tests/fixtures/launchctl/sonoma-gui-service.txt");
+    const VENTURA_SYSTEM_SERVICE: &str =
+        include_str!("../tests/fixtures/launchctl/ventura-system-service.txt");
+    const BIG_SUR_SERVICE: &str =
+        include_str!("../tests/fixtures/launchctl/big-sur-never-exited-service.txt");
+    const SONOMA_GUI_DOMAIN: &str =
+        include_str!("../tests/fixtures/launchctl/sonoma-gui-domain.txt");
+    const MONTEREY_SYSTEM_DOMAIN: &str =
+        include_str!("../tests/fixtures/launchctl/monterey-system-domain.txt");
+
+    fn service(pid: Option<u32>, last_exit_code: &str, label: &str) -> DomainService {
+        DomainService {
+            pid,
+            last_exit_code: Some(last_exit_code.to_string()),
+            label: label.to_string(),
         }
+    }
 
-        Ok(services)
+    fn field(key: &str, value: &str) -> PrintEntry {
+        PrintEntry::Field(key.to_string(), PrintValue::Scalar(value.to_string()))
     }
 
-    pub fn parse_print_service(
-        daemon: &Daemon,
-    ) -> Result<Option<(Option<u32>, Option<String>, String, Option<PathBuf>)>> {
-        let result = launchctl::print(daemon)?;
+    #[test]
+    fn split_field_separators() {
+        assert_eq!(split_field("state = running"), Some(("state".into(), "running")));
+        assert_eq!(split_field("PATH => /usr/bin:/bin"), Some(("PATH".into(), "/usr/bin:/bin")));
+        // the first separator wins, whichever kind it is
+        assert_eq!(split_field("a => b = c"), Some(("a".into(), "b = c")));
+        assert_eq!(split_field("a = b => c"), Some(("a".into(), "b => c")));
+        assert_eq!(split_field("arguments = {"), Some(("arguments".into(), "{")));
+        assert_eq!(split_field("properties ="), Some(("properties".into(), "")));
+        assert_eq!(split_field("/usr/bin/true"), None);
+        assert_eq!(split_field("--level=debug"), None);
+    }
 
-        if !result.success() {
-            return Ok(None);
-        }
+    #[test]
+    fn split_field_quoted_keys() {
+        assert_eq!(
+            split_field("\"com.apple.Siri.agent\" => disabled"),
+            Some(("com.apple.Siri.agent".into(), "disabled"))
+        );
+        // separators inside the quotes belong to the key
+        assert_eq!(split_field("\"a = b\" => enabled"), Some(("a = b".into(), "enabled")));
+        assert_eq!(split_field("\"a => b\" = {"), Some(("a => b".into(), "{")));
+    }
 
-        let output = &result.output;
+    #[test]
+    fn parse_tree_nests_blocks() {
+        let output = "gui/501/com.foo = {\n\tstate = running\n\targuments = {\n\t\t/bin/foo\n\t\t-x\n\t}\n\n\tenvironment = {\n\t\tA => 1\n\t}\n}\n";
+        let (name, root) = Parser::parse_tree(output).unwrap();
+        assert_eq!(name, "gui/501/com.foo");
+        assert_eq!(root.scalar("state"), Some("running"));
+        assert_eq!(root.block("arguments").unwrap().lines(), ["/bin/foo", "-x"]);
+        assert_eq!(root.block("environment").unwrap().entries, [field("A", "1")]);
+        assert_eq!(root.block("state"), None);
+        assert_eq!(root.scalar("arguments"), None);
+    }
+
+    #[test]
+    fn parse_tree_tolerates_truncation() {
+        assert_eq!(Parser::parse_tree(""), None);
+        assert_eq!(Parser::parse_tree("Could not find service \"x\" in domain for port"), None);
 
-        // Extract service name from first line: "system/com.apple.airportd = {"
-        let first_line = match output.lines().next() {
-            Some(line) => line,
-            None => return Ok(None),
+        let (name, root) =
+            Parser::parse_tree("\n\"system\" = {\n\targuments = {\n\t\t/bin/foo").unwrap();
+        assert_eq!(name, "system");
+        assert_eq!(root.block("arguments").unwrap().lines(), ["/bin/foo"]);
+    }
+
+    #[test]
+    fn lines_keep_arguments_containing_separators() {
+        let (_, root) = Parser::parse_tree(VENTURA_SYSTEM_SERVICE).unwrap();
+        assert_eq!(
+            root.block("arguments").unwrap().lines(),
+            ["/usr/local/sbin/backup", "--target = /Volumes/Backup"]
+        );
+    }
+
+    #[test]
+    fn sonoma_gui_service() {
+        let info = Parser::parse_service_info(SONOMA_GUI_SERVICE).unwrap();
+        assert_eq!(info.target, "gui/501/com.example.agent");
+        assert_eq!(
+            info.path,
+            Some(PathBuf::from("/Users/tester/Library/LaunchAgents/com.example.agent.plist"))
+        );
+        assert_eq!(info.service_type.as_deref(), Some("LaunchAgent"));
+        assert_eq!(info.state, ServiceState::Running);
+        assert_eq!(info.program.as_deref(), Some("/usr/local/bin/example"));
+        assert_eq!(
+            info.arguments,
+            ["/usr/local/bin/example", "--config", "/Users/tester/.example.conf", "--level=debug"]
+        );
+        assert_eq!(info.working_directory.as_deref(), Some("/Users/tester"));
+        assert_eq!(info.stdout_path.as_deref(), Some("/tmp/example.out"));
+        assert_eq!(info.stderr_path.as_deref(), Some("/tmp/example.err"));
+        assert_eq!(
+            info.inherited_environment["SSH_AUTH_SOCK"],
+            "/private/tmp/com.apple.launchd.abc/Listeners"
+        );
+        assert_eq!(info.default_environment["PATH"], "/usr/bin:/bin:/usr/sbin:/sbin");
+        assert_eq!(info.environment["EXAMPLE_MODE"], "production");
+        assert_eq!(info.domain.as_deref(), Some("gui/501"));
+        assert_eq!(info.pid, Some(4242));
+        assert_eq!(info.runs, Some(3));
+        assert_eq!(info.last_exit_code.as_deref(), Some("0"));
+        assert_eq!(info.spawn_type.as_deref(), Some("interactive (4)"));
+        assert_eq!(info.properties, ["keepalive", "runatload", "inferred program"]);
+        assert_eq!(info.other["jetsam priority"], "40");
+        assert_eq!(info.other["jetsam memory limit (active)"], "(unlimited)");
+
+        let [trigger] = info.event_triggers.as_slice() else {
+            panic!("expected one event trigger, got {:?}", info.event_triggers);
         };
-        let name = match first_line.split(" = {").next() {
-            Some(n) => n.trim().to_string(),
-            None => return Ok(None),
+        assert_eq!(trigger.name, "com.example.agent.watch");
+        assert_eq!(trigger.stream.as_deref(), Some("com.apple.fsevents.matching"));
+        assert!(trigger.keepalive);
+        assert_eq!(trigger.descriptor["Path"], "/Users/tester/Inbox");
+        assert_eq!(trigger.attributes["monitor"], "com.apple.UserEventAgent-Aqua");
+
+        let [endpoint] = info.endpoints.as_slice() else {
+            panic!("expected one endpoint, got {:?}", info.endpoints);
         };
+        assert_eq!(endpoint.name, "com.example.agent.xpc");
+        assert_eq!(endpoint.port.as_deref(), Some("0x4b03"));
+        assert!(!endpoint.active);
+        assert!(endpoint.managed);
+        assert_eq!(endpoint.attributes["watching"], "1");
+    }
 
-        if name.is_empty() {
-            return Ok(None);
-        }
+    #[test]
+    fn ventura_system_service() {
+        let info = Parser::parse_service_info(VENTURA_SYSTEM_SERVICE).unwrap();
+        assert_eq!(info.target, "system/com.example.backup");
+        assert_eq!(info.service_type.as_deref(), Some("LaunchDaemon"));
+        assert_eq!(info.state, ServiceState::NotRunning);
+        assert_eq!(info.domain.as_deref(), Some("system"));
+        assert_eq!(info.pid, None);
+        assert_eq!(info.runs, Some(12));
+        assert_eq!(info.last_exit_code.as_deref(), Some("1"));
+        assert_eq!(info.last_terminating_signal.as_deref(), Some("Terminated: 15"));
+        assert_eq!(info.run_interval.as_deref(), Some("3600 seconds"));
+        assert_eq!(info.spawn_type.as_deref(), Some("daemon (3)"));
+        assert!(info.properties.is_empty());
+        assert!(info.inherited_environment.is_empty());
+    }
+
+    #[test]
+    fn big_sur_service_that_never_ran() {
+        let info = Parser::parse_service_info(BIG_SUR_SERVICE).unwrap();
+        assert_eq!(info.target, "user/501/com.example.idle");
+        assert_eq!(info.service_type, None);
+        assert_eq!(info.state, ServiceState::Waiting);
+        assert_eq!(
+            info.domain.as_deref(),
+            Some("com.apple.xpc.launchd.user.domain.501.100006.Aqua")
+        );
+        assert_eq!(info.runs, Some(0));
+        assert_eq!(info.last_exit_code.as_deref(), Some("(never exited)"));
+        assert_eq!(info.arguments, ["/usr/bin/true"]);
+        assert!(info.inherited_environment.is_empty());
+        assert_eq!(info.properties, ["inferred program"]);
+    }
+
+    #[test]
+    fn service_info_needs_a_header() {
+        assert!(Parser::parse_service_info("").is_none());
+        assert!(Parser::parse_service_info("Bad request.\n").is_none());
+    }
+
+    #[test]
+    fn sonoma_gui_domain() {
+        let info = Parser::parse_domain_info(SONOMA_GUI_DOMAIN).unwrap();
+        assert_eq!(info.name, "gui/501");
+        assert_eq!(info.domain_type.as_deref(), Some("login"));
+        assert_eq!(
+            info.services,
+            [
+                service(Some(583), "0", "com.apple.Finder"),
+                service(Some(0), "-", "com.apple.cfprefsd.xpc.agent"),
+                service(None, "1", "com.example.failing"),
+                service(Some(612), "-", "com.apple.Dock.agent"),
+                service(None, "-9", "com.example.killed"),
+            ]
+        );
+        assert_eq!(info.subdomains, ["pid/583", "pid/612"]);
+        assert!(info.properties.is_empty());
+        assert_eq!(info.other["creator"], "loginwindow[411]");
+        assert_eq!(info.other["service count"], "473");
+    }
+
+    #[test]
+    fn disabled_services_both_spellings() {
+        let sonoma = Parser::parse_domain_info(SONOMA_GUI_DOMAIN).unwrap();
+        assert_eq!(
+            sonoma.disabled_services,
+            BTreeMap::from([
+                ("com.apple.Siri.agent".to_string(), true),
+                ("com.example.agent".to_string(), false),
+            ])
+        );
+
+        let monterey = Parser::parse_domain_info(MONTEREY_SYSTEM_DOMAIN).unwrap();
+        assert_eq!(
+            monterey.disabled_services,
+            BTreeMap::from([
+                ("com.apple.ftpd".to_string(), true),
+                ("com.example.daemon".to_string(), true),
+                ("com.openssh.sshd".to_string(), false),
+            ])
+        );
+
+        let garbled = "system = {\n\tdisabled services = {\n\t\t\"a\" => maybe\n\t}\n}\n";
+        assert!(Parser::parse_domain_info(garbled).unwrap().disabled_services.is_empty());
+    }
+
+    #[test]
+    fn domain_endpoints_and_unmanaged_processes() {
+        let info = Parser::parse_domain_info(SONOMA_GUI_DOMAIN).unwrap();
+        let endpoints: Vec<_> = info
+            .endpoints
+            .iter()
+            .map(|e| (e.name.as_str(), e.port.as_deref(), e.active, e.managed))
+            .collect();
+        assert_eq!(
+            endpoints,
+            [
+                ("com.apple.Finder", Some("0x1f203"), true, true),
+                ("com.apple.Dock.fullscreen", Some("0x1f303"), false, true),
+            ]
+        );
+
+        let [process] = info.unmanaged_processes.as_slice() else {
+            panic!("expected one unmanaged process, got {:?}", info.unmanaged_processes);
+        };
+        assert_eq!(process.name, "com.apple.xpc.launchd.unmanaged.loginwindow.411");
+        assert_eq!(process.attributes["active count"], "4");
+        let [endpoint] = process.endpoints.as_slice() else {
+            panic!("expected one dynamic endpoint, got {:?}", process.endpoints);
+        };
+        assert_eq!(endpoint.name, "com.apple.tsm.portname");
+        assert_eq!(endpoint.port.as_deref(), Some("0x1e903"));
+        assert!(endpoint.active);
+        assert!(!endpoint.managed);
+        assert_eq!(endpoint.attributes["reaped"], "0");
+    }
+
+    #[test]
+    fn monterey_system_domain() {
+        let info = Parser::parse_domain_info(MONTEREY_SYSTEM_DOMAIN).unwrap();
+        assert_eq!(info.name, "system");
+        assert_eq!(info.domain_type.as_deref(), Some("system"));
+        assert_eq!(info.subdomains, ["user/501", "gui/501", "pid/1"]);
+        assert_eq!(info.services.len(), 3);
+        assert_eq!(info.services[2], service(None, "78", "com.example.daemon"));
+        assert_eq!(info.endpoints.len(), 1);
+        assert!(info.unmanaged_processes.is_empty());
+        assert_eq!(info.properties, ["uncorked", "audit check done", "bootcache hack"]);
+    }
 
-        // Extract PID from "pid = 434" line
-        let pid = output
-            .lines()
-            .find(|line| line.trim().starts_with("pid ="))
-            .and_then(|line| {
-                line.trim()
-                    .strip_prefix("pid =")
-                    .and_then(|s| s.trim().parse::<u32>().ok())
-            });
-
-        // Extract last exit code from "last exit code = ..." line
-        let last_exit_code = output
-            .lines()
-            .find(|line| line.trim().starts_with("last exit code ="))
-            .and_then(|line| {
-                line.trim()
-                    .strip_prefix("last exit code =")
-                    .map(|s| s.trim().to_string())
-            });
-
-        // Extract source path from "path = /path/to/file.plist" line
-        let source_path = output
-            .lines()
-            .find(|line| line.trim().starts_with("path ="))
-            .and_then(|line| {
-                line.trim()
-                    .strip_prefix("path =")
-                    .map(|s| PathBuf::from(s.trim()))
-            });
-
-        Ok(Some((pid, last_exit_code, name, source_path)))
+    #[test]
+    fn short_service_rows_are_skipped() {
+        let output = "gui/501 = {\n\tservices = {\n\t\t583\n\t\t- 0\n\t\t12 - com.foo\n\t}\n}\n";
+        let info = Parser::parse_domain_info(output).unwrap();
+        assert_eq!(info.services, [service(Some(12), "-", "com.foo")]);
     }
 }
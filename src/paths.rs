@@ -0,0 +1,130 @@
+//! Every filesystem location angel reads or writes on launchd's behalf.
+//!
+//! All paths hang off a root, `/` by default. Point `ANGEL_ROOT` (or `root:` in `.angelrc`)
+//! at a fixture tree to run the registry, install and uninstall against it.
+
+use crate::angel::invoking_uid;
+use crate::config::Config;
+use crate::types::Domain;
+use nix::unistd::{self, User};
+use std::path::{Path, PathBuf};
+
+pub const ROOT_ENV: &str = "ANGEL_ROOT";
+
+#[derive(Debug, Clone)]
+pub struct Paths {
+    root: PathBuf,
+    home: Option<PathBuf>,
+}
+
+impl Paths {
+    /// `home` is the real home directory; it is re-rooted like every other path.
+    pub fn new(root: impl Into<PathBuf>, home: Option<PathBuf>) -> Self {
+        Self { root: root.into(), home }
+    }
+
+    /// Root from `ANGEL_ROOT`, then the config's `root`, then `/`. The home is the invoking
+    /// user's, see `home_dir`.
+    pub fn load(config: &Config) -> Self {
+        let root = std::env::var(ROOT_ENV)
+            .ok()
+            .filter(|root| !root.is_empty())
+            .or_else(|| config.root.clone())
+            .unwrap_or_else(|| "/".to_string());
+        Self::new(root, home_dir())
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Re-root an absolute path. Relative paths are returned unchanged.
+    pub fn resolve(&self, path: impl AsRef<Path>) -> PathBuf {
+        let path = path.as_ref();
+        match path.strip_prefix("/") {
+            Ok(relative) => self.root.join(relative),
+            Err(_) => path.to_path_buf(),
+        }
+    }
+
+    pub fn home(&self) -> Option<PathBuf> {
+        self.home.as_ref().map(|home| self.resolve(home))
+    }
+
+    pub fn apple_launch_daemons(&self) -> PathBuf {
+        self.resolve("/System/Library/LaunchDaemons")
+    }
+
+    pub fn apple_launch_agents(&self) -> PathBuf {
+        self.resolve("/System/Library/LaunchAgents")
+    }
+
+    pub fn apple_launch_angels(&self) -> PathBuf {
+        self.resolve("/System/Library/LaunchAngels")
+    }
+
+    pub fn launch_daemons(&self) -> PathBuf {
+        self.resolve("/Library/LaunchDaemons")
+    }
+
+    pub fn launch_agents(&self) -> PathBuf {
+        self.resolve("/Library/LaunchAgents")
+    }
+
+    /// `~/Library/LaunchAgents`
+    pub fn user_launch_agents(&self) -> Option<PathBuf> {
+        self.home().map(|home| home.join("Library/LaunchAgents"))
+    }
+
+    /// `~/.config/angel/{system,user,gui}`, where angel keeps plists it manages itself.
+    pub fn angel_dir(&self, domain: &Domain) -> Option<PathBuf> {
+        let name = match domain {
+            Domain::System => "system",
+            Domain::Gui(_) => "gui",
+            Domain::User(_) | Domain::Unknown => "user",
+        };
+        self.home().map(|home| home.join(".config/angel").join(name))
+    }
+
+    /// Which services each manifest has applied, so `apply --prune` knows what it owns.
+    pub fn apply_state(&self) -> Option<PathBuf> {
+        self.home().map(|home| home.join(".config/angel/applied.yaml"))
+    }
+
+    /// How `install` put each plist in place, so `uninstall` can undo it.
+    pub fn install_records(&self) -> Option<PathBuf> {
+        self.home().map(|home| home.join(".config/angel/installed.yaml"))
+    }
+
+    /// Whether `path` lives somewhere launchd insists is root-owned.
+    pub fn is_system_path(&self, path: &Path) -> bool {
+        path.starts_with(self.resolve("/Library")) || path.starts_with(self.resolve("/System"))
+    }
+
+    /// Where launchd keeps its enable/disable override databases.
+    pub fn launchd_db(&self) -> PathBuf {
+        self.resolve("/var/db/com.apple.xpc.launchd")
+    }
+
+    /// launchd's enable/disable override database for a domain.
+    pub fn disabled_overrides(&self, domain: &Domain) -> PathBuf {
+        let db = self.launchd_db();
+        match domain {
+            Domain::User(uid) | Domain::Gui(uid) => db.join(format!("disabled.{}.plist", uid)),
+            Domain::System | Domain::Unknown => db.join("disabled.plist"),
+        }
+    }
+}
+
+/// The home of the user angel acts for. Under sudo that is whoever ran sudo, looked up from
+/// `SUDO_UID` like the uid itself, since `$HOME` is root's or theirs depending on sudoers.
+/// Otherwise `$HOME`.
+fn home_dir() -> Option<PathBuf> {
+    let uid = invoking_uid();
+    if uid != unistd::geteuid()
+        && let Ok(Some(user)) = User::from_uid(uid)
+    {
+        return Some(user.dir);
+    }
+    std::env::var("HOME").ok().filter(|home| !home.is_empty()).map(PathBuf::from)
+}
@@ -0,0 +1,92 @@
+//! Every question angel asks goes through here, so scripts can run it without a terminal.
+//!
+//! With `--yes`, `--no-input`, `ANGEL_NONINTERACTIVE` or no TTY on stdin, nothing is shown:
+//! confirmations are answered automatically and logged to stderr, and questions without a
+//! safe automatic answer become errors that name the flag to pass instead.
+
+use crate::error::{Result, UserError};
+use crate::output::stderr;
+use crate::styles::styles::prefix;
+use crossterm::style::Color;
+use std::fmt::Display;
+use std::io::IsTerminal;
+use std::sync::OnceLock;
+
+pub const NONINTERACTIVE_ENV: &str = "ANGEL_NONINTERACTIVE";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    Interactive,
+    /// Answer confirmations with their default
+    NoInput,
+    /// Answer confirmations with yes
+    AssumeYes,
+}
+
+static MODE: OnceLock<Mode> = OnceLock::new();
+
+/// Must be called before any prompt is shown.
+pub fn init(yes: bool, no_input: bool) {
+    let env = std::env::var(NONINTERACTIVE_ENV)
+        .is_ok_and(|value| !matches!(value.as_str(), "" | "0" | "false"));
+    let mode = match () {
+        _ if yes => Mode::AssumeYes,
+        _ if no_input || env || !std::io::stdin().is_terminal() => Mode::NoInput,
+        _ => Mode::Interactive,
+    };
+    MODE.set(mode).ok();
+}
+
+pub fn mode() -> Mode {
+    *MODE.get_or_init(|| Mode::Interactive)
+}
+
+pub fn is_interactive() -> bool {
+    mode() == Mode::Interactive
+}
+
+fn log_choice(prompt: &str, answer: impl Display) {
+    stderr::writelogln(prefix(Color::Cyan, "AUTO"), format!("{} → {}", prompt, answer));
+}
+
+pub fn confirm(prompt: &str, default: bool) -> Result<bool> {
+    let answer = match mode() {
+        Mode::Interactive => {
+            return Ok(dialoguer::Confirm::new()
+                .with_prompt(prompt)
+                .default(default)
+                .interact()?);
+        }
+        Mode::AssumeYes => true,
+        Mode::NoInput => default,
+    };
+    log_choice(prompt, if answer { "yes" } else { "no" });
+    Ok(answer)
+}
+
+/// Pick one of `items`. Without a terminal there is no safe default, so this fails and points
+/// at `hint`, the flag that makes the choice up front.
+pub fn select<T: Display>(prompt: &str, items: &[T], default: usize, hint: &str) -> Result<usize> {
+    match is_interactive() {
+        true => Ok(dialoguer::Select::new()
+            .with_prompt(prompt)
+            .items(items)
+            .default(default)
+            .interact()?),
+        false => Err(UserError::InputRequired(format!("{} ({})", prompt, hint)).into()),
+    }
+}
+
+/// Free-text input. Optional answers are left empty without a terminal; required ones fail.
+pub fn input(prompt: &str, optional: bool, hint: &str) -> Result<String> {
+    match (is_interactive(), optional) {
+        (true, _) => {
+            Ok(dialoguer::Input::new().with_prompt(prompt).allow_empty(optional).interact_text()?)
+        }
+        (false, true) => {
+            log_choice(prompt, "(empty)");
+            Ok(String::new())
+        }
+        (false, false) => Err(UserError::InputRequired(format!("{} ({})", prompt, hint)).into()),
+    }
+}
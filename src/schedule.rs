@@ -0,0 +1,611 @@
+//! Periodic schedules: cron syntax for `StartCalendarInterval`, and when a job fires next.
+//!
+//! A cron expression expands to the calendar entries launchd understands: `30 2 * * 1-5`
+//! becomes five entries, `Minute=30,Hour=2,Weekday=1` through `Weekday=5`. launchd fires when
+//! any entry matches, and like cron treats an entry with both `Day` and `Weekday` as matching
+//! on either.
+
+use crate::types::{CalendarInterval, Plist};
+use jiff::civil::{Date, Time};
+use jiff::tz::TimeZone;
+use jiff::{ToSpan, Zoned};
+use std::collections::{BTreeSet, HashSet};
+
+/// launchd copes badly with thousands of entries; past this, StartInterval is the better fit.
+const MAX_ENTRIES: usize = 1000;
+
+/// How far ahead to look for a calendar match. `Day=29,Month=2` can be eight years out.
+const SEARCH_DAYS: i32 = 366 * 8;
+
+struct Field {
+    name: &'static str,
+    min: i32,
+    max: i32,
+    /// Three-letter names, numbered from `min`
+    names: &'static [&'static str],
+}
+
+const MINUTE: Field = Field { name: "minute", min: 0, max: 59, names: &[] };
+const HOUR: Field = Field { name: "hour", min: 0, max: 23, names: &[] };
+const DAY: Field = Field { name: "day", min: 1, max: 31, names: &[] };
+const MONTH: Field = Field {
+    name: "month",
+    min: 1,
+    max: 12,
+    names: &["jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec"],
+};
+/// 0 and 7 are both Sunday, as in cron and launchd
+const WEEKDAY: Field = Field {
+    name: "weekday",
+    min: 0,
+    max: 7,
+    names: &["sun", "mon", "tue", "wed", "thu", "fri", "sat"],
+};
+
+/// A cron expression as a command-line value, e.g. `--cron "30 2 * * 1-5"`.
+#[derive(Debug, Clone)]
+pub struct Cron(pub Vec<CalendarInterval>);
+
+impl std::str::FromStr for Cron {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_cron(s).map(Cron)
+    }
+}
+
+/// Expand a five-field cron expression (or `@daily` and friends) into calendar entries.
+pub fn parse_cron(expr: &str) -> Result<Vec<CalendarInterval>, String> {
+    let expr = match expr.trim() {
+        "@hourly" => "0 * * * *",
+        "@daily" | "@midnight" => "0 0 * * *",
+        "@weekly" => "0 0 * * 0",
+        "@monthly" => "0 0 1 * *",
+        "@yearly" | "@annually" => "0 0 1 1 *",
+        "@reboot" => return Err("@reboot has no calendar equivalent, use RunAtLoad".to_string()),
+        expr => expr,
+    };
+    let fields: Vec<&str> = expr.split_whitespace().collect();
+    let [minute, hour, day, month, weekday] = fields.as_slice() else {
+        return Err(format!(
+            "expected 5 fields (minute hour day month weekday), got {}",
+            fields.len()
+        ));
+    };
+    let minutes = parse_field(minute, &MINUTE)?;
+    let hours = parse_field(hour, &HOUR)?;
+    let days = parse_field(day, &DAY)?;
+    let months = parse_field(month, &MONTH)?;
+    let weekdays = parse_field(weekday, &WEEKDAY)?;
+
+    // cron fires on either the day or the weekday when both are restricted; separate entries
+    // say the same thing to launchd without relying on how it combines the two keys
+    let (days, weekdays) = (wildcard_or_values(&days), wildcard_or_values(&weekdays));
+    let day_entries: Vec<(Option<i32>, Option<i32>)> = match (days[0], weekdays[0]) {
+        (Some(_), Some(_)) => days
+            .iter()
+            .map(|day| (*day, None))
+            .chain(weekdays.iter().map(|weekday| (None, *weekday)))
+            .collect(),
+        _ => days.iter().flat_map(|day| weekdays.iter().map(|weekday| (*day, *weekday))).collect(),
+    };
+    let (minutes, hours, months) =
+        (wildcard_or_values(&minutes), wildcard_or_values(&hours), wildcard_or_values(&months));
+
+    let count = minutes.len() * hours.len() * months.len() * day_entries.len();
+    if count > MAX_ENTRIES {
+        return Err(format!(
+            "`{}` expands to {} calendar entries, use an interval instead",
+            expr, count
+        ));
+    }
+
+    let mut intervals = Vec::with_capacity(count);
+    for &month in &months {
+        for &(day, weekday) in &day_entries {
+            for &hour in &hours {
+                for &minute in &minutes {
+                    intervals.push(CalendarInterval {
+                        minute,
+                        hour,
+                        day,
+                        weekday,
+                        month,
+                        ..Default::default()
+                    });
+                }
+            }
+        }
+    }
+    Ok(intervals)
+}
+
+/// Parse one field into its values, or `None` when it matches everything.
+fn parse_field(text: &str, field: &Field) -> Result<Option<Vec<i32>>, String> {
+    let mut values = BTreeSet::new();
+    for item in text.split(',') {
+        let (range, step) = match item.split_once('/') {
+            Some((range, step)) => match step.parse::<usize>() {
+                Ok(step) if step > 0 => (range, step),
+                _ => return Err(format!("invalid step `{}` in {} field", step, field.name)),
+            },
+            None => (item, 1),
+        };
+        let (start, end) = match range.split_once('-') {
+            _ if range == "*" => (field.min, field.max),
+            Some((start, end)) => (parse_value(start, field)?, parse_value(end, field)?),
+            // `5/15` means from 5 to the end in steps of 15
+            None if step > 1 => (parse_value(range, field)?, field.max),
+            None => {
+                let value = parse_value(range, field)?;
+                (value, value)
+            }
+        };
+        if start > end {
+            return Err(format!("range `{}` in {} field runs backwards", range, field.name));
+        }
+        values.extend((start..=end).step_by(step));
+    }
+
+    if field.name == WEEKDAY.name && values.remove(&7) {
+        values.insert(0);
+    }
+    let max = if field.name == WEEKDAY.name { 6 } else { field.max };
+    match (field.min..=max).all(|value| values.contains(&value)) {
+        true => Ok(None),
+        false => Ok(Some(values.into_iter().collect())),
+    }
+}
+
+fn parse_value(text: &str, field: &Field) -> Result<i32, String> {
+    let lower = text.to_lowercase();
+    let value = match field.names.iter().position(|name| lower.starts_with(name)) {
+        Some(index) => index as i32 + field.min,
+        None => text.parse().map_err(|_| format!("`{}` is not a valid {}", text, field.name))?,
+    };
+    match (field.min..=field.max).contains(&value) {
+        true => Ok(value),
+        false => {
+            Err(format!("{} {} is out of range {}-{}", field.name, value, field.min, field.max))
+        }
+    }
+}
+
+fn wildcard_or_values(values: &Option<Vec<i32>>) -> Vec<Option<i32>> {
+    match values {
+        Some(values) => values.iter().map(|value| Some(*value)).collect(),
+        None => vec![None],
+    }
+}
+
+/// Render calendar entries back as one cron expression, when they form one. Entries that
+/// don't, e.g. `Hour=9` alongside `Minute=30,Hour=17`, give `None`.
+pub fn to_cron(intervals: &[CalendarInterval]) -> Option<String> {
+    if intervals.is_empty() {
+        return None;
+    }
+    let normalized: HashSet<[Option<i32>; 5]> = intervals.iter().map(cron_order).collect();
+
+    let mut fields = Vec::with_capacity(5);
+    let mut combinations = 1;
+    for index in 0..5 {
+        let values: Vec<Option<i32>> = normalized.iter().map(|entry| entry[index]).collect();
+        match (values.iter().all(Option::is_none), values.iter().any(Option::is_none)) {
+            (true, _) => fields.push("*".to_string()),
+            (false, true) => return None,
+            (false, false) => {
+                let set: BTreeSet<i32> = values.into_iter().flatten().collect();
+                combinations *= set.len();
+                fields.push(render_values(&set));
+            }
+        }
+    }
+    (combinations == normalized.len()).then(|| fields.join(" "))
+}
+
+/// Minute, hour, day, month, weekday, with Sunday as 0.
+fn cron_order(interval: &CalendarInterval) -> [Option<i32>; 5] {
+    let weekday = interval.weekday.map(|weekday| if weekday == 7 { 0 } else { weekday });
+    [interval.minute, interval.hour, interval.day, interval.month, weekday]
+}
+
+/// `1,2,3,5` as `1-3,5`.
+fn render_values(values: &BTreeSet<i32>) -> String {
+    let mut runs: Vec<(i32, i32)> = Vec::new();
+    for &value in values {
+        match runs.last_mut() {
+            Some((_, end)) if *end + 1 == value => *end = value,
+            _ => runs.push((value, value)),
+        }
+    }
+    runs.into_iter()
+        .map(|(start, end)| match end - start {
+            0 => start.to_string(),
+            1 => format!("{},{}", start, end),
+            _ => format!("{}-{}", start, end),
+        })
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// The plist's StartInterval and StartCalendarInterval, e.g. `every 1h, 30 2 * * 1-5`.
+pub fn describe(plist: &Plist) -> Option<String> {
+    let mut parts = Vec::new();
+    if let Some(seconds) = plist.start_interval {
+        parts.push(format!("every {}", humanize(seconds)));
+    }
+    if let Some(calendar) = &plist.start_calendar_interval {
+        parts.extend(describe_calendar(calendar.as_slice()));
+    }
+    (!parts.is_empty()).then(|| parts.join(", "))
+}
+
+/// Entries as cron expressions where they form one. Several `--cron` flags produce entries of
+/// different shapes, so entries are grouped by which keys they set and each group rendered.
+fn describe_calendar(entries: &[CalendarInterval]) -> Vec<String> {
+    if let Some(cron) = to_cron(entries) {
+        return vec![cron];
+    }
+    let mut groups: Vec<([bool; 5], Vec<CalendarInterval>)> = Vec::new();
+    for entry in entries {
+        let shape = cron_order(entry).map(|value| value.is_some());
+        match groups.iter_mut().find(|(group_shape, _)| *group_shape == shape) {
+            Some((_, group)) => group.push(entry.clone()),
+            None => groups.push((shape, vec![entry.clone()])),
+        }
+    }
+    groups
+        .into_iter()
+        .flat_map(|(_, group)| match to_cron(&group) {
+            Some(cron) => vec![cron],
+            None => group.iter().map(|entry| entry.to_string()).collect(),
+        })
+        .collect()
+}
+
+fn humanize(seconds: i32) -> String {
+    match seconds {
+        s if s > 0 && s % 86400 == 0 => format!("{}d", s / 86400),
+        s if s > 0 && s % 3600 == 0 => format!("{}h", s / 3600),
+        s if s > 0 && s % 60 == 0 => format!("{}m", s / 60),
+        s => format!("{}s", s),
+    }
+}
+
+/// One line for humans: the schedule and when it next fires, e.g.
+/// `30 2 * * 1-5, next Mon 2026-10-19 02:30`.
+pub fn summary(plist: &Plist) -> Option<String> {
+    let schedule = describe(plist)?;
+    let now = Zoned::now();
+    match next_runs(plist, &now, 1).first() {
+        Some(run) => Some(format!("{}, next {}", schedule, run.display())),
+        None => Some(schedule),
+    }
+}
+
+/// When a job is next started by its schedule.
+#[derive(Debug, Clone)]
+pub struct Run {
+    pub at: Zoned,
+    /// StartInterval counts from when the job was loaded, which launchd doesn't report, so
+    /// interval runs are counted from now instead
+    pub estimated: bool,
+}
+
+impl Run {
+    pub fn display(&self) -> String {
+        let at = self.at.strftime("%a %Y-%m-%d %H:%M").to_string();
+        match self.estimated {
+            true => format!("~{}", at),
+            false => at,
+        }
+    }
+}
+
+/// The next `n` scheduled runs after `now`, soonest first. Empty for jobs with no schedule.
+pub fn next_runs(plist: &Plist, now: &Zoned, n: usize) -> Vec<Run> {
+    let mut runs = Vec::new();
+    if let Some(seconds) = plist.start_interval.filter(|seconds| *seconds > 0) {
+        let mut at = now.clone();
+        for _ in 0..n {
+            let Ok(next) = at.checked_add(seconds.seconds()) else { break };
+            runs.push(Run { at: next.clone(), estimated: true });
+            at = next;
+        }
+    }
+    if let Some(calendar) = &plist.start_calendar_interval {
+        let mut after = now.clone();
+        for _ in 0..n {
+            let Some(next) = next_calendar_run(calendar.as_slice(), &after) else { break };
+            runs.push(Run { at: next.clone(), estimated: false });
+            after = next;
+        }
+    }
+    runs.sort_by_key(|run| run.at.timestamp());
+    runs.truncate(n);
+    runs
+}
+
+/// The first minute strictly after `after` that any entry matches, in `after`'s time zone.
+fn next_calendar_run(intervals: &[CalendarInterval], after: &Zoned) -> Option<Zoned> {
+    intervals.iter().filter_map(|interval| next_match(interval, after)).min_by_key(Zoned::timestamp)
+}
+
+fn next_match(interval: &CalendarInterval, after: &Zoned) -> Option<Zoned> {
+    let tz: TimeZone = after.time_zone().clone();
+    let start = after.date();
+    let hours: Vec<i8> = match interval.hour {
+        Some(hour) => vec![i8::try_from(hour).ok()?],
+        None => (0..24).collect(),
+    };
+    let minutes: Vec<i8> = match interval.minute {
+        Some(minute) => vec![i8::try_from(minute).ok()?],
+        None => (0..60).collect(),
+    };
+
+    // An entry read from disk can hold e.g. Hour=24, which never matches
+    let times: Vec<Time> = hours
+        .iter()
+        .flat_map(|&hour| minutes.iter().map(move |&minute| Time::new(hour, minute, 0, 0)))
+        .collect::<Result<_, _>>()
+        .ok()?;
+
+    let mut date = start;
+    for _ in 0..SEARCH_DAYS {
+        if date_matches(interval, date) {
+            for &time in &times {
+                let Ok(datetime) = date.to_datetime(time).to_zoned(tz.clone()) else { continue };
+                if datetime.timestamp() > after.timestamp() {
+                    return Some(datetime);
+                }
+            }
+        }
+        date = date.tomorrow().ok()?;
+    }
+    None
+}
+
+fn date_matches(interval: &CalendarInterval, date: Date) -> bool {
+    let weekday = i32::from(date.weekday().to_sunday_zero_offset());
+    let month_matches = interval.month.is_none_or(|month| month == i32::from(date.month()));
+    let day_matches = interval.day.map(|day| day == i32::from(date.day()));
+    let weekday_matches = interval.weekday.map(|w| w % 7 == weekday);
+    month_matches
+        && match (day_matches, weekday_matches) {
+            (None, None) => true,
+            (Some(matches), None) | (None, Some(matches)) => matches,
+            (Some(day), Some(weekday)) => day || weekday,
+        }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jiff::civil::date;
+
+    /// US Eastern without the tz database: DST starts 2026-03-08 and ends 2026-11-01.
+    fn eastern() -> TimeZone {
+        TimeZone::posix("EST5EDT,M3.2.0,M11.1.0").unwrap()
+    }
+
+    fn at(year: i16, month: i8, day: i8, hour: i8, minute: i8) -> Zoned {
+        date(year, month, day).at(hour, minute, 0, 0).to_zoned(eastern()).unwrap()
+    }
+
+    fn cron_plist(expr: &str) -> Plist {
+        let calendar = parse_cron(expr).unwrap();
+        Plist { start_calendar_interval: Some(calendar.into()), ..Default::default() }
+    }
+
+    fn runs(plist: &Plist, now: &Zoned, n: usize) -> Vec<String> {
+        next_runs(plist, now, n).iter().map(|run| run.at.strftime("%F %T %Z").to_string()).collect()
+    }
+
+    #[test]
+    fn cron_round_trips() {
+        for expr in [
+            "30 2 * * 1-5",
+            "0 9 1,15 1-3 *",
+            "0,15,30,45 * * * *",
+            "0 0 * * 0",
+            "5 4 * 6 *",
+            "* * * * *",
+            "0 8-10,12 * * 1,3",
+        ] {
+            let calendar = parse_cron(expr).unwrap();
+            assert_eq!(to_cron(&calendar).as_deref(), Some(expr), "{}", expr);
+        }
+    }
+
+    #[test]
+    fn cron_aliases_names_and_steps() {
+        let formatted = |expr: &str| to_cron(&parse_cron(expr).unwrap());
+        assert_eq!(formatted("@daily").as_deref(), Some("0 0 * * *"));
+        assert_eq!(formatted("@weekly").as_deref(), Some("0 0 * * 0"));
+        assert_eq!(formatted("@yearly").as_deref(), Some("0 0 1 1 *"));
+        assert_eq!(formatted("*/15 * * * *").as_deref(), Some("0,15,30,45 * * * *"));
+        assert_eq!(formatted("5/20 * * * *").as_deref(), Some("5,25,45 * * * *"));
+        assert_eq!(formatted("0 0 * JAN-mar sun").as_deref(), Some("0 0 * 1-3 0"));
+        // 7 is Sunday too, and a full week is no restriction at all
+        assert_eq!(formatted("0 0 * * 7").as_deref(), Some("0 0 * * 0"));
+        assert_eq!(formatted("0 0 * * 0-7").as_deref(), Some("0 0 * * *"));
+    }
+
+    #[test]
+    fn cron_expands_to_calendar_entries() {
+        let calendar = parse_cron("30 2 * * 1-5").unwrap();
+        assert_eq!(calendar.len(), 5);
+        assert_eq!(calendar[0].to_string(), "Minute=30,Hour=2,Weekday=1");
+        assert_eq!(parse_cron("@hourly").unwrap()[0].to_string(), "Minute=0");
+
+        // day and weekday both restricted fire on either, so they become separate entries
+        let calendar = parse_cron("0 12 1 * 1").unwrap();
+        let entries: Vec<String> = calendar.iter().map(ToString::to_string).collect();
+        assert_eq!(entries, ["Minute=0,Hour=12,Day=1", "Minute=0,Hour=12,Weekday=1"]);
+        assert_eq!(to_cron(&calendar), None);
+        assert_eq!(describe_calendar(&calendar), ["0 12 1 * *", "0 12 * * 1"]);
+    }
+
+    #[test]
+    fn to_cron_rejects_irregular_entries() {
+        assert_eq!(to_cron(&[]), None);
+        let irregular: Vec<CalendarInterval> =
+            ["Hour=9", "Minute=30,Hour=17"].iter().map(|s| s.parse().unwrap()).collect();
+        assert_eq!(to_cron(&irregular), None);
+        let partial: Vec<CalendarInterval> =
+            ["Minute=0,Hour=9", "Minute=30,Hour=17"].iter().map(|s| s.parse().unwrap()).collect();
+        assert_eq!(to_cron(&partial), None);
+    }
+
+    #[test]
+    fn invalid_cron_fields() {
+        for (expr, error) in [
+            ("", "expected 5 fields (minute hour day month weekday), got 0"),
+            ("* * * *", "expected 5 fields (minute hour day month weekday), got 4"),
+            ("* * * * * *", "expected 5 fields (minute hour day month weekday), got 6"),
+            ("60 * * * *", "minute 60 is out of range 0-59"),
+            ("* 24 * * *", "hour 24 is out of range 0-23"),
+            ("* * 0 * *", "day 0 is out of range 1-31"),
+            ("* * * 13 *", "month 13 is out of range 1-12"),
+            ("* * * * 8", "weekday 8 is out of range 0-7"),
+            ("*/0 * * * *", "invalid step `0` in minute field"),
+            ("*/x * * * *", "invalid step `x` in minute field"),
+            ("30-10 * * * *", "range `30-10` in minute field runs backwards"),
+            ("abc * * * *", "`abc` is not a valid minute"),
+            ("* * * foo *", "`foo` is not a valid month"),
+            ("@reboot", "@reboot has no calendar equivalent, use RunAtLoad"),
+            (
+                "0-58 0-22 * * *",
+                "`0-58 0-22 * * *` expands to 1357 calendar entries, use an interval instead",
+            ),
+        ] {
+            assert_eq!(parse_cron(expr).unwrap_err(), error, "{}", expr);
+        }
+    }
+
+    #[test]
+    fn next_runs_skip_short_months() {
+        let plist = cron_plist("0 9 31 * *");
+        assert_eq!(
+            runs(&plist, &at(2026, 4, 1, 0, 0), 3),
+            ["2026-05-31 09:00:00 EDT", "2026-07-31 09:00:00 EDT", "2026-08-31 09:00:00 EDT"]
+        );
+
+        let leap_day = cron_plist("0 0 29 2 *");
+        assert_eq!(runs(&leap_day, &at(2026, 1, 1, 0, 0), 1), ["2028-02-29 00:00:00 EST"]);
+    }
+
+    #[test]
+    fn next_runs_cross_month_and_year_ends() {
+        let plist = cron_plist("59 23 * * *");
+        assert_eq!(
+            runs(&plist, &at(2026, 12, 31, 23, 59), 2),
+            ["2027-01-01 23:59:00 EST", "2027-01-02 23:59:00 EST"]
+        );
+
+        let first = cron_plist("0 0 1 * *");
+        assert_eq!(
+            runs(&first, &at(2026, 1, 31, 12, 0), 2),
+            ["2026-02-01 00:00:00 EST", "2026-03-01 00:00:00 EST"]
+        );
+    }
+
+    #[test]
+    fn next_runs_fire_on_day_or_weekday() {
+        // 2026-10-18 is a Sunday
+        let plist = cron_plist("0 12 1 * 1");
+        assert_eq!(
+            runs(&plist, &at(2026, 10, 18, 12, 0), 4),
+            [
+                "2026-10-19 12:00:00 EDT",
+                "2026-10-26 12:00:00 EDT",
+                "2026-11-01 12:00:00 EST",
+                "2026-11-02 12:00:00 EST",
+            ]
+        );
+    }
+
+    #[test]
+    fn next_runs_across_spring_forward() {
+        // 02:30 doesn't exist on 2026-03-08; the run moves to the same offset past the gap
+        let plist = cron_plist("30 2 * * *");
+        assert_eq!(
+            runs(&plist, &at(2026, 3, 7, 12, 0), 2),
+            ["2026-03-08 03:30:00 EDT", "2026-03-09 02:30:00 EDT"]
+        );
+    }
+
+    #[test]
+    fn next_runs_across_fall_back() {
+        // 01:30 happens twice on 2026-11-01; the job runs once, at the first
+        let plist = cron_plist("30 1 * * *");
+        let next = next_runs(&plist, &at(2026, 10, 31, 12, 0), 2);
+        let times: Vec<String> =
+            next.iter().map(|run| run.at.strftime("%F %T %Z").to_string()).collect();
+        assert_eq!(times, ["2026-11-01 01:30:00 EDT", "2026-11-02 01:30:00 EST"]);
+        assert_eq!((&next[1].at - &next[0].at).get_hours(), 25);
+        assert!(next.iter().all(|run| !run.estimated));
+    }
+
+    #[test]
+    fn next_runs_skip_out_of_range_entries() {
+        let now = at(2026, 6, 1, 11, 0);
+        let entry = |hour: i32, minute: i32| CalendarInterval {
+            hour: Some(hour),
+            minute: Some(minute),
+            ..Default::default()
+        };
+        for (hour, minute) in [(24, 0), (12, 60), (-1, 0), (12, -5), (300, 0)] {
+            let plist = Plist {
+                start_calendar_interval: Some(vec![entry(hour, minute)].into()),
+                ..Default::default()
+            };
+            assert_eq!(runs(&plist, &now, 1), Vec::<String>::new(), "{}:{}", hour, minute);
+            assert!(describe(&plist).is_some());
+        }
+
+        // The valid entries alongside still run
+        let plist = Plist {
+            start_calendar_interval: Some(vec![entry(24, 0), entry(13, 0)].into()),
+            ..Default::default()
+        };
+        assert_eq!(runs(&plist, &now, 1), ["2026-06-01 13:00:00 EDT"]);
+    }
+
+    #[test]
+    fn next_runs_merge_intervals_and_calendars() {
+        let plist = Plist {
+            start_interval: Some(5400),
+            start_calendar_interval: Some(parse_cron("0 13 * * *").unwrap().into()),
+            ..Default::default()
+        };
+        let next = next_runs(&plist, &at(2026, 6, 1, 11, 0), 3);
+        let summary: Vec<(String, bool)> =
+            next.iter().map(|run| (run.at.strftime("%H:%M").to_string(), run.estimated)).collect();
+        assert_eq!(
+            summary,
+            [
+                ("12:30".to_string(), true),
+                ("13:00".to_string(), false),
+                ("14:00".to_string(), true)
+            ]
+        );
+        assert_eq!(next[0].display(), "~Mon 2026-06-01 12:30");
+        assert_eq!(next[1].display(), "Mon 2026-06-01 13:00");
+
+        assert!(next_runs(&Plist::default(), &at(2026, 6, 1, 11, 0), 3).is_empty());
+    }
+
+    #[test]
+    fn describe_schedules() {
+        let plist = Plist {
+            start_interval: Some(3600),
+            start_calendar_interval: Some(parse_cron("30 2 * * 1-5").unwrap().into()),
+            ..Default::default()
+        };
+        assert_eq!(describe(&plist).as_deref(), Some("every 1h, 30 2 * * 1-5"));
+        assert_eq!(describe(&Plist::default()), None);
+        assert_eq!(humanize(90), "90s");
+        assert_eq!(humanize(172800), "2d");
+    }
+}
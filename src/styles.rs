@@ -42,10 +42,10 @@ pub mod styles {
     }
 
     fn compress_path(path: &Path) -> String {
-        if let Some(home) = dirs::home_dir() {
-            if let Ok(relative) = path.strip_prefix(&home) {
-                return format!("~/{}", relative.display());
-            }
+        if let Some(home) = dirs::home_dir()
+            && let Ok(relative) = path.strip_prefix(&home)
+        {
+            return format!("~/{}", relative.display());
         }
         path.display().to_string()
     }
@@ -0,0 +1,758 @@
+//! Typed model of the launchd.plist(5) vocabulary, including deprecated keys.
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer, de, ser};
+use std::collections::BTreeMap;
+
+/// A value launchd accepts either on its own or as an array, e.g. `StartCalendarInterval`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum OneOrMany<T> {
+    One(T),
+    Many(Vec<T>),
+}
+
+impl<T> OneOrMany<T> {
+    pub fn as_slice(&self) -> &[T] {
+        match self {
+            OneOrMany::One(value) => std::slice::from_ref(value),
+            OneOrMany::Many(values) => values,
+        }
+    }
+
+    pub fn first(&self) -> Option<&T> {
+        self.as_slice().first()
+    }
+}
+
+// Untagged enums buffer their input through serde, which turns plist dates into strings, so the
+// container enums pick a variant from a `plist::Value` instead.
+impl<'de, T: de::DeserializeOwned> Deserialize<'de> for OneOrMany<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = plist::Value::deserialize(deserializer)?;
+        match value {
+            plist::Value::Array(_) => plist::from_value(&value).map(OneOrMany::Many),
+            _ => plist::from_value(&value).map(OneOrMany::One),
+        }
+        .map_err(de::Error::custom)
+    }
+}
+
+impl<T> From<Vec<T>> for OneOrMany<T> {
+    fn from(mut values: Vec<T>) -> Self {
+        match values.len() {
+            1 => OneOrMany::One(values.remove(0)),
+            _ => OneOrMany::Many(values),
+        }
+    }
+}
+
+/// A launchd job definition.
+///
+/// The derived impls only see the typed keys (`remote = "Self"`). The public `Deserialize` and
+/// `Serialize` impls below wrap them so that unknown keys survive a read/write round trip.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+#[serde(remote = "Self")]
+pub struct Plist {
+    #[serde(rename = "Label", skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+
+    #[serde(rename = "Disabled", skip_serializing_if = "Option::is_none")]
+    pub disabled: Option<bool>,
+
+    #[serde(rename = "UserName", skip_serializing_if = "Option::is_none")]
+    pub user_name: Option<String>,
+
+    #[serde(rename = "GroupName", skip_serializing_if = "Option::is_none")]
+    pub group_name: Option<String>,
+
+    #[serde(rename = "inetdCompatibility", skip_serializing_if = "Option::is_none")]
+    pub inetd_compatibility: Option<InetdCompatibility>,
+
+    #[serde(rename = "LimitLoadToHosts", skip_serializing_if = "Option::is_none")]
+    pub limit_load_to_hosts: Option<Vec<String>>,
+
+    #[serde(rename = "LimitLoadFromHosts", skip_serializing_if = "Option::is_none")]
+    pub limit_load_from_hosts: Option<Vec<String>>,
+
+    #[serde(rename = "LimitLoadToSessionType", skip_serializing_if = "Option::is_none")]
+    pub limit_load_to_session_type: Option<OneOrMany<String>>,
+
+    #[serde(rename = "LimitLoadToHardware", skip_serializing_if = "Option::is_none")]
+    pub limit_load_to_hardware: Option<BTreeMap<String, Vec<String>>>,
+
+    #[serde(rename = "LimitLoadFromHardware", skip_serializing_if = "Option::is_none")]
+    pub limit_load_from_hardware: Option<BTreeMap<String, Vec<String>>>,
+
+    #[serde(rename = "Program", skip_serializing_if = "Option::is_none")]
+    pub program: Option<String>,
+
+    #[serde(rename = "BundleProgram", skip_serializing_if = "Option::is_none")]
+    pub bundle_program: Option<String>,
+
+    #[serde(rename = "ProgramArguments", skip_serializing_if = "Option::is_none")]
+    pub program_arguments: Option<Vec<String>>,
+
+    #[serde(rename = "EnableGlobbing", skip_serializing_if = "Option::is_none")]
+    pub enable_globbing: Option<bool>,
+
+    #[serde(rename = "EnableTransactions", skip_serializing_if = "Option::is_none")]
+    pub enable_transactions: Option<bool>,
+
+    #[serde(rename = "EnablePressuredExit", skip_serializing_if = "Option::is_none")]
+    pub enable_pressured_exit: Option<bool>,
+
+    #[serde(rename = "OnDemand", skip_serializing_if = "Option::is_none")]
+    pub on_demand: Option<bool>,
+
+    #[serde(rename = "ServiceIPC", skip_serializing_if = "Option::is_none")]
+    pub service_ipc: Option<bool>,
+
+    #[serde(rename = "KeepAlive", skip_serializing_if = "Option::is_none")]
+    pub keep_alive: Option<KeepAlive>,
+
+    #[serde(rename = "RunAtLoad", skip_serializing_if = "Option::is_none")]
+    pub run_at_load: Option<bool>,
+
+    #[serde(rename = "RootDirectory", skip_serializing_if = "Option::is_none")]
+    pub root_directory: Option<String>,
+
+    #[serde(rename = "WorkingDirectory", skip_serializing_if = "Option::is_none")]
+    pub working_directory: Option<String>,
+
+    #[serde(rename = "EnvironmentVariables", skip_serializing_if = "Option::is_none")]
+    pub environment_variables: Option<BTreeMap<String, String>>,
+
+    #[serde(rename = "Umask", skip_serializing_if = "Option::is_none")]
+    pub umask: Option<Umask>,
+
+    #[serde(rename = "TimeOut", skip_serializing_if = "Option::is_none")]
+    pub time_out: Option<i32>,
+
+    #[serde(rename = "ExitTimeOut", skip_serializing_if = "Option::is_none")]
+    pub exit_time_out: Option<i32>,
+
+    #[serde(rename = "ThrottleInterval", skip_serializing_if = "Option::is_none")]
+    pub throttle_interval: Option<i32>,
+
+    #[serde(rename = "InitGroups", skip_serializing_if = "Option::is_none")]
+    pub init_groups: Option<bool>,
+
+    #[serde(rename = "WatchPaths", skip_serializing_if = "Option::is_none")]
+    pub watch_paths: Option<Vec<String>>,
+
+    #[serde(rename = "QueueDirectories", skip_serializing_if = "Option::is_none")]
+    pub queue_directories: Option<Vec<String>>,
+
+    #[serde(rename = "StartOnMount", skip_serializing_if = "Option::is_none")]
+    pub start_on_mount: Option<bool>,
+
+    #[serde(rename = "StartInterval", skip_serializing_if = "Option::is_none")]
+    pub start_interval: Option<i32>,
+
+    #[serde(rename = "StartCalendarInterval", skip_serializing_if = "Option::is_none")]
+    pub start_calendar_interval: Option<OneOrMany<CalendarInterval>>,
+
+    #[serde(rename = "StandardInPath", skip_serializing_if = "Option::is_none")]
+    pub standard_in_path: Option<String>,
+
+    #[serde(rename = "StandardOutPath", skip_serializing_if = "Option::is_none")]
+    pub standard_out_path: Option<String>,
+
+    #[serde(rename = "StandardErrorPath", skip_serializing_if = "Option::is_none")]
+    pub standard_error_path: Option<String>,
+
+    #[serde(rename = "Debug", skip_serializing_if = "Option::is_none")]
+    pub debug: Option<bool>,
+
+    #[serde(rename = "WaitForDebugger", skip_serializing_if = "Option::is_none")]
+    pub wait_for_debugger: Option<bool>,
+
+    #[serde(rename = "SoftResourceLimits", skip_serializing_if = "Option::is_none")]
+    pub soft_resource_limits: Option<ResourceLimits>,
+
+    #[serde(rename = "HardResourceLimits", skip_serializing_if = "Option::is_none")]
+    pub hard_resource_limits: Option<ResourceLimits>,
+
+    #[serde(rename = "Nice", skip_serializing_if = "Option::is_none")]
+    pub nice: Option<i32>,
+
+    #[serde(rename = "ProcessType", skip_serializing_if = "Option::is_none")]
+    pub process_type: Option<String>,
+
+    #[serde(rename = "AbandonProcessGroup", skip_serializing_if = "Option::is_none")]
+    pub abandon_process_group: Option<bool>,
+
+    #[serde(rename = "LowPriorityIO", skip_serializing_if = "Option::is_none")]
+    pub low_priority_io: Option<bool>,
+
+    #[serde(rename = "LowPriorityBackgroundIO", skip_serializing_if = "Option::is_none")]
+    pub low_priority_background_io: Option<bool>,
+
+    #[serde(rename = "MaterializeDatalessFiles", skip_serializing_if = "Option::is_none")]
+    pub materialize_dataless_files: Option<bool>,
+
+    #[serde(rename = "LaunchOnlyOnce", skip_serializing_if = "Option::is_none")]
+    pub launch_only_once: Option<bool>,
+
+    #[serde(rename = "SessionCreate", skip_serializing_if = "Option::is_none")]
+    pub session_create: Option<bool>,
+
+    #[serde(rename = "LegacyTimers", skip_serializing_if = "Option::is_none")]
+    pub legacy_timers: Option<bool>,
+
+    #[serde(rename = "HopefullyExitsFirst", skip_serializing_if = "Option::is_none")]
+    pub hopefully_exits_first: Option<bool>,
+
+    #[serde(rename = "HopefullyExitsLast", skip_serializing_if = "Option::is_none")]
+    pub hopefully_exits_last: Option<bool>,
+
+    #[serde(rename = "MachServices", skip_serializing_if = "Option::is_none")]
+    pub mach_services: Option<BTreeMap<String, MachService>>,
+
+    #[serde(rename = "Sockets", skip_serializing_if = "Option::is_none")]
+    pub sockets: Option<BTreeMap<String, OneOrMany<Socket>>>,
+
+    #[serde(rename = "LaunchEvents", skip_serializing_if = "Option::is_none")]
+    pub launch_events: Option<BTreeMap<String, BTreeMap<String, plist::Value>>>,
+
+    #[serde(rename = "AssociatedBundleIdentifiers", skip_serializing_if = "Option::is_none")]
+    pub associated_bundle_identifiers: Option<OneOrMany<String>>,
+
+    /// Keys not modelled above, kept so that rewriting a plist never drops a user's settings.
+    #[serde(skip)]
+    pub extra: plist::Dictionary,
+}
+
+/// Routes through the derived, typed-keys-only impls of a type that keeps unknown keys.
+struct TypedKeys<T>(T);
+
+/// Gives each type its public (de)serialization, which splits unknown keys into `extra` and
+/// merges them back. The types derive with `#[serde(remote = "Self")]` and skip `extra`, since
+/// `#[serde(flatten)]` doesn't round-trip through the plist crate.
+macro_rules! keep_unknown_keys {
+    ($($ty:ident),+ $(,)?) => {$(
+        impl<'de> Deserialize<'de> for TypedKeys<$ty> {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                $ty::deserialize(deserializer).map(TypedKeys)
+            }
+        }
+
+        impl Serialize for TypedKeys<&$ty> {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                $ty::serialize(self.0, serializer)
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $ty {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let dict = plist::Dictionary::deserialize(deserializer)?;
+                let (typed, extra) = split_unknown_keys::<$ty, D::Error>(dict)?;
+                Ok($ty { extra, ..typed })
+            }
+        }
+
+        impl Serialize for $ty {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                let known = plist::to_value(&TypedKeys(self)).map_err(ser::Error::custom)?;
+                merge_unknown_keys(known, &self.extra).serialize(serializer)
+            }
+        }
+    )+};
+}
+
+keep_unknown_keys!(
+    Plist,
+    KeepAliveConditions,
+    CalendarInterval,
+    InetdCompatibility,
+    ResourceLimits,
+    MachServiceOptions,
+    Socket,
+);
+
+/// Parses the typed keys of `dict`, returning whatever they don't account for as unknown keys.
+fn split_unknown_keys<T, E>(mut dict: plist::Dictionary) -> Result<(T, plist::Dictionary), E>
+where
+    TypedKeys<T>: de::DeserializeOwned,
+    for<'a> TypedKeys<&'a T>: Serialize,
+    E: de::Error,
+{
+    let value = plist::Value::Dictionary(dict.clone());
+    let TypedKeys(typed) = plist::from_value::<TypedKeys<T>>(&value).map_err(E::custom)?;
+
+    // Whatever the typed keys don't serialize back out is an unknown key
+    if let plist::Value::Dictionary(known) =
+        plist::to_value(&TypedKeys(&typed)).map_err(E::custom)?
+    {
+        for key in known.keys() {
+            dict.remove(key);
+        }
+    }
+    Ok((typed, dict))
+}
+
+/// Adds unknown keys back to serialized typed keys, never overriding a typed key.
+fn merge_unknown_keys(known: plist::Value, extra: &plist::Dictionary) -> plist::Dictionary {
+    let mut dict = match known {
+        plist::Value::Dictionary(dict) => dict,
+        _ => plist::Dictionary::new(),
+    };
+    for (key, value) in extra {
+        if !dict.contains_key(key) {
+            dict.insert(key.clone(), value.clone());
+        }
+    }
+    dict
+}
+
+/// `KeepAlive` is either a plain bool or a dictionary of conditions.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum KeepAlive {
+    Always(bool),
+    Conditions(KeepAliveConditions),
+}
+
+impl<'de> Deserialize<'de> for KeepAlive {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        match plist::Value::deserialize(deserializer)? {
+            plist::Value::Boolean(always) => Ok(KeepAlive::Always(always)),
+            value => {
+                plist::from_value(&value).map(KeepAlive::Conditions).map_err(de::Error::custom)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+#[serde(remote = "Self")]
+pub struct KeepAliveConditions {
+    #[serde(rename = "SuccessfulExit", skip_serializing_if = "Option::is_none")]
+    pub successful_exit: Option<bool>,
+
+    #[serde(rename = "NetworkState", skip_serializing_if = "Option::is_none")]
+    pub network_state: Option<bool>,
+
+    #[serde(rename = "PathState", skip_serializing_if = "Option::is_none")]
+    pub path_state: Option<BTreeMap<String, bool>>,
+
+    #[serde(rename = "OtherJobEnabled", skip_serializing_if = "Option::is_none")]
+    pub other_job_enabled: Option<BTreeMap<String, bool>>,
+
+    #[serde(rename = "OtherJobActive", skip_serializing_if = "Option::is_none")]
+    pub other_job_active: Option<BTreeMap<String, bool>>,
+
+    #[serde(rename = "Crashed", skip_serializing_if = "Option::is_none")]
+    pub crashed: Option<bool>,
+
+    #[serde(rename = "AfterInitialDemand", skip_serializing_if = "Option::is_none")]
+    pub after_initial_demand: Option<bool>,
+
+    /// Keys not modelled above, kept like `Plist::extra`
+    #[serde(skip)]
+    pub extra: plist::Dictionary,
+}
+
+/// One `StartCalendarInterval` entry. Missing fields are wildcards.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+#[serde(remote = "Self")]
+pub struct CalendarInterval {
+    #[serde(rename = "Minute", skip_serializing_if = "Option::is_none")]
+    pub minute: Option<i32>,
+
+    #[serde(rename = "Hour", skip_serializing_if = "Option::is_none")]
+    pub hour: Option<i32>,
+
+    #[serde(rename = "Day", skip_serializing_if = "Option::is_none")]
+    pub day: Option<i32>,
+
+    #[serde(rename = "Weekday", skip_serializing_if = "Option::is_none")]
+    pub weekday: Option<i32>,
+
+    #[serde(rename = "Month", skip_serializing_if = "Option::is_none")]
+    pub month: Option<i32>,
+
+    /// Keys not modelled above, kept like `Plist::extra`
+    #[serde(skip)]
+    pub extra: plist::Dictionary,
+}
+
+/// Parses `Minute=0,Hour=9` style entries, keys case-insensitive. Values are range-checked.
+impl std::str::FromStr for CalendarInterval {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut interval = CalendarInterval::default();
+        for pair in s.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+            let (key, value) = pair
+                .split_once('=')
+                .ok_or_else(|| format!("expected KEY=VALUE, got `{}`", pair))?;
+            let value: i32 =
+                value.trim().parse().map_err(|_| format!("`{}` is not a number", value.trim()))?;
+            let (field, min, max) = match key.trim().to_lowercase().as_str() {
+                "minute" => (&mut interval.minute, 0, 59),
+                "hour" => (&mut interval.hour, 0, 23),
+                "day" => (&mut interval.day, 1, 31),
+                // 0 and 7 are both Sunday
+                "weekday" => (&mut interval.weekday, 0, 7),
+                "month" => (&mut interval.month, 1, 12),
+                other => return Err(format!("unknown calendar key `{}`", other)),
+            };
+            if !(min..=max).contains(&value) {
+                let key = key.trim();
+                return Err(format!("{} {} is out of range {}-{}", key, value, min, max));
+            }
+            *field = Some(value);
+        }
+        match interval == CalendarInterval::default() {
+            true => Err("calendar interval is empty".to_string()),
+            false => Ok(interval),
+        }
+    }
+}
+
+/// Formats as `Minute=0,Hour=9`, which `FromStr` reads back.
+impl std::fmt::Display for CalendarInterval {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let fields = [
+            ("Minute", self.minute),
+            ("Hour", self.hour),
+            ("Day", self.day),
+            ("Month", self.month),
+            ("Weekday", self.weekday),
+        ];
+        let pairs: Vec<String> = fields
+            .iter()
+            .filter_map(|(key, value)| value.map(|value| format!("{}={}", key, value)))
+            .collect();
+        write!(f, "{}", pairs.join(","))
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+#[serde(remote = "Self")]
+pub struct InetdCompatibility {
+    #[serde(rename = "Wait", skip_serializing_if = "Option::is_none")]
+    pub wait: Option<bool>,
+
+    /// Keys not modelled above, kept like `Plist::extra`
+    #[serde(skip)]
+    pub extra: plist::Dictionary,
+}
+
+/// launchd accepts the umask as an integer or, for octal notation, as a string.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum Umask {
+    Integer(i32),
+    Octal(String),
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+#[serde(remote = "Self")]
+pub struct ResourceLimits {
+    #[serde(rename = "Core", skip_serializing_if = "Option::is_none")]
+    pub core: Option<i64>,
+
+    #[serde(rename = "CPU", skip_serializing_if = "Option::is_none")]
+    pub cpu: Option<i64>,
+
+    #[serde(rename = "Data", skip_serializing_if = "Option::is_none")]
+    pub data: Option<i64>,
+
+    #[serde(rename = "FileSize", skip_serializing_if = "Option::is_none")]
+    pub file_size: Option<i64>,
+
+    #[serde(rename = "MemoryLock", skip_serializing_if = "Option::is_none")]
+    pub memory_lock: Option<i64>,
+
+    #[serde(rename = "NumberOfFiles", skip_serializing_if = "Option::is_none")]
+    pub number_of_files: Option<i64>,
+
+    #[serde(rename = "NumberOfProcesses", skip_serializing_if = "Option::is_none")]
+    pub number_of_processes: Option<i64>,
+
+    #[serde(rename = "ResidentSetSize", skip_serializing_if = "Option::is_none")]
+    pub resident_set_size: Option<i64>,
+
+    #[serde(rename = "Stack", skip_serializing_if = "Option::is_none")]
+    pub stack: Option<i64>,
+
+    /// Keys not modelled above, kept like `Plist::extra`
+    #[serde(skip)]
+    pub extra: plist::Dictionary,
+}
+
+/// A `MachServices` entry is either `true` or a dictionary of options.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum MachService {
+    Enabled(bool),
+    Options(MachServiceOptions),
+}
+
+impl<'de> Deserialize<'de> for MachService {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        match plist::Value::deserialize(deserializer)? {
+            plist::Value::Boolean(enabled) => Ok(MachService::Enabled(enabled)),
+            value => plist::from_value(&value).map(MachService::Options).map_err(de::Error::custom),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+#[serde(remote = "Self")]
+pub struct MachServiceOptions {
+    #[serde(rename = "ResetAtClose", skip_serializing_if = "Option::is_none")]
+    pub reset_at_close: Option<bool>,
+
+    #[serde(rename = "HideUntilCheckIn", skip_serializing_if = "Option::is_none")]
+    pub hide_until_check_in: Option<bool>,
+
+    /// Keys not modelled above, kept like `Plist::extra`
+    #[serde(skip)]
+    pub extra: plist::Dictionary,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+#[serde(remote = "Self")]
+pub struct Socket {
+    #[serde(rename = "SockType", skip_serializing_if = "Option::is_none")]
+    pub sock_type: Option<String>,
+
+    #[serde(rename = "SockPassive", skip_serializing_if = "Option::is_none")]
+    pub sock_passive: Option<bool>,
+
+    #[serde(rename = "SockNodeName", skip_serializing_if = "Option::is_none")]
+    pub sock_node_name: Option<String>,
+
+    #[serde(rename = "SockServiceName", skip_serializing_if = "Option::is_none")]
+    pub sock_service_name: Option<SockServiceName>,
+
+    #[serde(rename = "SockFamily", skip_serializing_if = "Option::is_none")]
+    pub sock_family: Option<String>,
+
+    #[serde(rename = "SockProtocol", skip_serializing_if = "Option::is_none")]
+    pub sock_protocol: Option<String>,
+
+    #[serde(rename = "SockPathName", skip_serializing_if = "Option::is_none")]
+    pub sock_path_name: Option<String>,
+
+    #[serde(rename = "SecureSocketWithKey", skip_serializing_if = "Option::is_none")]
+    pub secure_socket_with_key: Option<String>,
+
+    #[serde(rename = "SockPathOwner", skip_serializing_if = "Option::is_none")]
+    pub sock_path_owner: Option<i32>,
+
+    #[serde(rename = "SockPathGroup", skip_serializing_if = "Option::is_none")]
+    pub sock_path_group: Option<i32>,
+
+    #[serde(rename = "SockPathMode", skip_serializing_if = "Option::is_none")]
+    pub sock_path_mode: Option<i32>,
+
+    #[serde(rename = "Bonjour", skip_serializing_if = "Option::is_none")]
+    pub bonjour: Option<Bonjour>,
+
+    #[serde(rename = "MulticastGroup", skip_serializing_if = "Option::is_none")]
+    pub multicast_group: Option<String>,
+
+    /// Keys not modelled above, kept like `Plist::extra`
+    #[serde(skip)]
+    pub extra: plist::Dictionary,
+}
+
+/// `SockServiceName` is a port number or a service name from /etc/services.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum SockServiceName {
+    Port(i32),
+    Name(String),
+}
+
+/// `Bonjour` registration: on/off, a single service name, or a list of names.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum Bonjour {
+    Enabled(bool),
+    Name(String),
+    Names(Vec<String>),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const AGENT: &[u8] = include_bytes!("../../tests/fixtures/plists/agent.plist");
+    const DAEMON: &[u8] = include_bytes!("../../tests/fixtures/plists/daemon.plist");
+    const DEPRECATED: &[u8] = include_bytes!("../../tests/fixtures/plists/deprecated.plist");
+    const VENDOR: &[u8] = include_bytes!("../../tests/fixtures/plists/vendor.plist");
+    const NESTED: &[u8] = include_bytes!("../../tests/fixtures/plists/nested.plist");
+
+    /// Parse `bytes` into a `Plist`, write it back out and compare with the untyped original.
+    fn assert_round_trips(bytes: &[u8]) -> Plist {
+        let parsed: Plist = plist::from_bytes(bytes).unwrap();
+        let original = plist::Value::from_reader(std::io::Cursor::new(bytes)).unwrap();
+        assert_eq!(plist::to_value(&parsed).unwrap(), original);
+
+        let mut xml = Vec::new();
+        plist::to_writer_xml(&mut xml, &parsed).unwrap();
+        assert_eq!(plist::from_bytes::<Plist>(&xml).unwrap(), parsed);
+        parsed
+    }
+
+    #[test]
+    fn agent_round_trips() {
+        let plist = assert_round_trips(AGENT);
+        assert!(plist.extra.is_empty());
+        assert_eq!(plist.label.as_deref(), Some("com.example.sync"));
+        assert_eq!(plist.program_arguments.as_deref().map(<[String]>::len), Some(2));
+        assert_eq!(plist.environment_variables.unwrap()["RUST_LOG"], "info");
+        let Some(KeepAlive::Conditions(conditions)) = plist.keep_alive else {
+            panic!("KeepAlive should parse as conditions");
+        };
+        assert_eq!(conditions.successful_exit, Some(false));
+        assert!(conditions.path_state.unwrap()["/tmp/sync.enabled"]);
+        let calendar = plist.start_calendar_interval.unwrap();
+        assert_eq!(
+            calendar.as_slice(),
+            [
+                CalendarInterval { hour: Some(9), minute: Some(0), ..Default::default() },
+                CalendarInterval { weekday: Some(1), ..Default::default() },
+            ]
+        );
+        assert_eq!(plist.limit_load_to_session_type, Some(OneOrMany::One("Aqua".to_string())));
+    }
+
+    #[test]
+    fn daemon_round_trips() {
+        let plist = assert_round_trips(DAEMON);
+        assert!(plist.extra.is_empty());
+        assert_eq!(plist.umask, Some(Umask::Integer(18)));
+        assert_eq!(plist.hard_resource_limits.unwrap().number_of_files, Some(8192));
+
+        let mach_services = plist.mach_services.unwrap();
+        assert_eq!(mach_services["com.example.server.xpc"], MachService::Enabled(true));
+        assert!(matches!(
+            &mach_services["com.example.server.private"],
+            MachService::Options(options) if options.hide_until_check_in == Some(true)
+        ));
+
+        let sockets = plist.sockets.unwrap();
+        let listener = sockets["Listeners"].first().unwrap();
+        assert_eq!(listener.sock_service_name, Some(SockServiceName::Port(8080)));
+        assert_eq!(listener.bonjour, Some(Bonjour::Names(vec!["http".to_string()])));
+        assert!(matches!(&sockets["Control"], OneOrMany::Many(control) if control.len() == 1));
+        assert_eq!(
+            plist.associated_bundle_identifiers,
+            Some(OneOrMany::One("com.example.Server".to_string()))
+        );
+    }
+
+    #[test]
+    fn deprecated_keys_round_trip() {
+        let plist = assert_round_trips(DEPRECATED);
+        assert!(plist.extra.is_empty());
+        assert_eq!(plist.disabled, Some(true));
+        assert_eq!(plist.on_demand, Some(false));
+        assert_eq!(plist.service_ipc, Some(true));
+        assert_eq!(plist.umask, Some(Umask::Octal("022".to_string())));
+        assert!(matches!(plist.start_calendar_interval, Some(OneOrMany::One(_))));
+    }
+
+    #[test]
+    fn unknown_and_vendor_keys_round_trip() {
+        let plist = assert_round_trips(VENDOR);
+        assert_eq!(plist.program.as_deref(), Some("/opt/vendor/bin/agent"));
+        assert_eq!(plist.run_at_load, Some(true));
+        let mut unknown: Vec<&str> = plist.extra.keys().map(String::as_str).collect();
+        unknown.sort();
+        assert_eq!(
+            unknown,
+            ["POSIXSpawnType", "_AdditionalProperties", "com.example.vendor.Settings"]
+        );
+    }
+
+    #[test]
+    fn typed_fields_win_over_extra() {
+        let mut plist: Plist = plist::from_bytes(VENDOR).unwrap();
+        plist.extra.insert("Program".to_string(), plist::Value::from("/stale"));
+        plist.program = Some("/usr/bin/true".to_string());
+        let value = plist::to_value(&plist).unwrap();
+        let dict = value.as_dictionary().unwrap();
+        assert_eq!(dict["Program"].as_string(), Some("/usr/bin/true"));
+        assert!(dict.contains_key("POSIXSpawnType"));
+    }
+
+    #[test]
+    fn default_plist_serializes_empty() {
+        let value = plist::to_value(&Plist::default()).unwrap();
+        assert_eq!(value, plist::Value::Dictionary(plist::Dictionary::new()));
+    }
+
+    #[test]
+    fn calendar_interval_from_str() {
+        let interval: CalendarInterval = "minute=30, Hour=2,Weekday=7".parse().unwrap();
+        assert_eq!(interval.minute, Some(30));
+        assert_eq!(interval.hour, Some(2));
+        assert_eq!(interval.weekday, Some(7));
+        assert_eq!(interval.to_string().parse::<CalendarInterval>().unwrap(), interval);
+
+        assert!("".parse::<CalendarInterval>().is_err());
+        assert!("Minute".parse::<CalendarInterval>().is_err());
+        assert!("Second=5".parse::<CalendarInterval>().is_err());
+        assert!("Hour=nine".parse::<CalendarInterval>().is_err());
+    }
+
+    #[test]
+    fn calendar_interval_out_of_range() {
+        for entry in
+            ["Minute=99", "Minute=-1", "Hour=30", "Day=0", "Day=32", "Weekday=8", "Month=13"]
+        {
+            assert!(entry.parse::<CalendarInterval>().is_err(), "{} should be rejected", entry);
+        }
+        assert_eq!(
+            "Hour=30".parse::<CalendarInterval>().unwrap_err(),
+            "Hour 30 is out of range 0-23"
+        );
+    }
+
+    #[test]
+    fn unknown_nested_keys_round_trip() {
+        let plist = assert_round_trips(NESTED);
+        assert!(plist.extra.is_empty());
+
+        let Some(KeepAlive::Conditions(conditions)) = &plist.keep_alive else {
+            panic!("KeepAlive should parse as conditions");
+        };
+        assert_eq!(conditions.successful_exit, Some(false));
+        assert!(conditions.extra.contains_key("FutureKey"));
+
+        let services = plist.mach_services.as_ref().unwrap();
+        let Some(MachService::Options(options)) = services.get("com.example.nested.xpc") else {
+            panic!("MachServices entry should parse as options");
+        };
+        assert_eq!(options.reset_at_close, Some(true));
+        assert_eq!(
+            options.extra.get("DrainMessagesOnCrash").and_then(plist::Value::as_string),
+            Some("All")
+        );
+
+        let socket = plist.sockets.as_ref().unwrap()["Listener"].first().unwrap();
+        assert_eq!(socket.sock_service_name, Some(SockServiceName::Port(8080)));
+        assert!(socket.extra.contains_key("SockTrafficClass"));
+
+        let limits = plist.soft_resource_limits.as_ref().unwrap();
+        assert_eq!(limits.number_of_files, Some(1024));
+        assert!(limits.extra.contains_key("Priority"));
+
+        let inetd = plist.inetd_compatibility.as_ref().unwrap();
+        assert_eq!(inetd.wait, Some(true));
+        assert!(inetd.extra.contains_key("Instances"));
+
+        let calendar = plist.start_calendar_interval.as_ref().unwrap().first().unwrap();
+        assert_eq!(calendar.hour, Some(3));
+        let mut keys: Vec<&String> = calendar.extra.keys().collect();
+        keys.sort();
+        assert_eq!(keys, ["Jitter", "Since", "Tag"]);
+    }
+}
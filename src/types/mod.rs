@@ -0,0 +1,198 @@
+mod launchd;
+mod runtime;
+
+pub use launchd::*;
+pub use runtime::*;
+
+use serde::{Serialize, Serializer};
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Domain {
+    System,
+    User(u32),
+    Gui(u32),
+    Unknown,
+}
+
+impl Domain {
+    /// Split a fully qualified service target such as `gui/501/com.foo` or `system/com.foo`.
+    pub fn parse_target(target: &str) -> Option<(Domain, &str)> {
+        if let Some(label) = target.strip_prefix("system/") {
+            return Some((Domain::System, label));
+        }
+        let (kind, rest) = target.split_once('/')?;
+        let (uid, label) = rest.split_once('/')?;
+        let uid = uid.parse().ok()?;
+        match kind {
+            "user" => Some((Domain::User(uid), label)),
+            "gui" => Some((Domain::Gui(uid), label)),
+            _ => None,
+        }
+    }
+
+    /// The other per-user domain. launchd loads `~/Library/LaunchAgents` into `gui/<uid>` for
+    /// a logged-in user and into `user/<uid>` otherwise.
+    pub fn sibling(&self) -> Option<Domain> {
+        match self {
+            Domain::User(uid) => Some(Domain::Gui(*uid)),
+            Domain::Gui(uid) => Some(Domain::User(*uid)),
+            _ => None,
+        }
+    }
+
+    pub fn from_plist(plist: &Plist, uid: u32, default: Domain) -> Self {
+        let session_type = plist.limit_load_to_session_type.as_ref().and_then(|s| s.first());
+        if let Some(session_type) = session_type {
+            let d = match session_type.as_str() {
+                "Aqua" => Domain::Gui(uid),
+                "Background" | "LoginWindow" => Domain::User(uid),
+                "System" => Domain::System,
+                _ => Domain::Unknown,
+            };
+            if d != Domain::Unknown { d } else { default }
+        } else {
+            default
+        }
+    }
+}
+
+/// A domain as named on the command line; the uid comes from the invoking user.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, clap::ValueEnum, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DomainKind {
+    Gui,
+    User,
+    System,
+}
+
+impl DomainKind {
+    pub fn domain(self, uid: u32) -> Domain {
+        match self {
+            DomainKind::Gui => Domain::Gui(uid),
+            DomainKind::User => Domain::User(uid),
+            DomainKind::System => Domain::System,
+        }
+    }
+}
+
+impl fmt::Display for Domain {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Domain::System => write!(f, "system"),
+            Domain::User(uid) => write!(f, "user/{}", uid),
+            Domain::Gui(uid) => write!(f, "gui/{}", uid),
+            Domain::Unknown => write!(f, "Unknown"),
+        }
+    }
+}
+
+impl Serialize for Domain {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ForWhom {
+    User,
+    Apple,
+    ThirdParty,
+    Angel,
+}
+
+#[derive(Debug, Clone)]
+pub struct Daemon {
+    pub name: String,
+    pub source_path: Option<PathBuf>,
+    pub domain: Domain,
+    pub for_use_by: ForWhom,
+    pub plist: Option<Plist>,
+    pub pid: Option<u32>,
+    pub last_exit_code: Option<String>,
+    /// Whether launchd has the service disabled: its override, else the plist's `Disabled`
+    /// key. `None` when neither says.
+    pub disabled: Option<bool>,
+}
+
+impl Daemon {
+    pub fn new(
+        name: String,
+        source_path: Option<PathBuf>,
+        domain: Domain,
+        for_use_by: ForWhom,
+        plist: Option<Plist>,
+        pid: Option<u32>,
+        last_exit_code: Option<String>,
+    ) -> Self {
+        let disabled = plist.as_ref().and_then(|plist| plist.disabled);
+        Self { name, source_path, domain, for_use_by, plist, pid, last_exit_code, disabled }
+    }
+
+    pub fn from_plist(
+        plist: Plist,
+        path: Option<PathBuf>,
+        default_domain: Domain,
+        for_use_by: ForWhom,
+        uid: u32,
+    ) -> Self {
+        let name = plist.label.clone().unwrap_or_else(|| {
+            path.as_ref()
+                .and_then(|p| p.file_stem())
+                .and_then(|s| s.to_str())
+                .unwrap_or("unknown")
+                .to_string()
+        });
+        let domain = Domain::from_plist(&plist, uid, default_domain);
+        let disabled = plist.disabled;
+        Self {
+            name,
+            source_path: path,
+            domain,
+            for_use_by,
+            plist: Some(plist),
+            pid: None,
+            last_exit_code: None,
+            disabled,
+        }
+    }
+
+    pub fn domain_str(&self) -> String {
+        self.domain.to_string()
+    }
+}
+
+/// The machine-readable view of a daemon emitted by `--format json|yaml|plist`.
+///
+/// Field names are a stable contract (see README). `runtime` is only filled by commands that
+/// query the service directly (`status`, `print`).
+#[derive(Debug, Serialize)]
+pub struct DaemonRecord<'a> {
+    pub name: &'a str,
+    pub domain: &'a Domain,
+    pub for_use_by: ForWhom,
+    pub source_path: Option<&'a Path>,
+    pub pid: Option<u32>,
+    pub last_exit_code: Option<&'a str>,
+    pub disabled: Option<bool>,
+    pub plist: Option<&'a Plist>,
+    pub runtime: Option<&'a ServiceInfo>,
+}
+
+impl Daemon {
+    pub fn record<'a>(&'a self, runtime: Option<&'a ServiceInfo>) -> DaemonRecord<'a> {
+        DaemonRecord {
+            name: &self.name,
+            domain: &self.domain,
+            for_use_by: self.for_use_by,
+            source_path: self.source_path.as_deref(),
+            pid: self.pid,
+            last_exit_code: self.last_exit_code.as_deref(),
+            disabled: self.disabled,
+            plist: self.plist.as_ref(),
+            runtime,
+        }
+    }
+}
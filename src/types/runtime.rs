@@ -0,0 +1,139 @@
+use serde::{Serialize, Serializer};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::PathBuf;
+
+/// Run state reported by `launchctl print` for a service.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ServiceState {
+    Running,
+    NotRunning,
+    Waiting,
+    Spawned,
+    Launched,
+    Exited,
+    Stopped,
+    Other(String),
+}
+
+impl ServiceState {
+    pub fn parse(s: &str) -> Self {
+        match s {
+            "running" => ServiceState::Running,
+            "not running" => ServiceState::NotRunning,
+            "waiting" => ServiceState::Waiting,
+            "spawned" => ServiceState::Spawned,
+            "launched" => ServiceState::Launched,
+            "exited" => ServiceState::Exited,
+            "stopped" => ServiceState::Stopped,
+            other => ServiceState::Other(other.to_string()),
+        }
+    }
+}
+
+impl Serialize for ServiceState {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl fmt::Display for ServiceState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ServiceState::Running => write!(f, "running"),
+            ServiceState::NotRunning => write!(f, "not running"),
+            ServiceState::Waiting => write!(f, "waiting"),
+            ServiceState::Spawned => write!(f, "spawned"),
+            ServiceState::Launched => write!(f, "launched"),
+            ServiceState::Exited => write!(f, "exited"),
+            ServiceState::Stopped => write!(f, "stopped"),
+            ServiceState::Other(s) => write!(f, "{}", s),
+        }
+    }
+}
+
+/// Parsed `launchctl print <domain>/<label>` output.
+#[derive(Debug, Clone, Serialize)]
+pub struct ServiceInfo {
+    /// Fully qualified target from the header line, e.g. `gui/501/com.apple.Finder`
+    pub target: String,
+    pub path: Option<PathBuf>,
+    pub service_type: Option<String>,
+    pub state: ServiceState,
+    pub program: Option<String>,
+    pub arguments: Vec<String>,
+    pub working_directory: Option<String>,
+    pub stdout_path: Option<String>,
+    pub stderr_path: Option<String>,
+    pub inherited_environment: BTreeMap<String, String>,
+    pub default_environment: BTreeMap<String, String>,
+    pub environment: BTreeMap<String, String>,
+    pub domain: Option<String>,
+    pub pid: Option<u32>,
+    pub runs: Option<u32>,
+    pub last_exit_code: Option<String>,
+    pub last_terminating_signal: Option<String>,
+    pub spawn_type: Option<String>,
+    pub run_interval: Option<String>,
+    pub properties: Vec<String>,
+    pub endpoints: Vec<Endpoint>,
+    pub event_triggers: Vec<EventTrigger>,
+    /// Scalar fields not modelled above, keyed by their launchctl name
+    pub other: BTreeMap<String, String>,
+}
+
+impl ServiceInfo {
+    pub fn has_property(&self, property: &str) -> bool {
+        self.properties.iter().any(|p| p == property)
+    }
+}
+
+/// A Mach endpoint, either from a `"name" = { … }` block or a `port flags name` row.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct Endpoint {
+    pub name: String,
+    pub port: Option<String>,
+    pub active: bool,
+    pub managed: bool,
+    pub attributes: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct EventTrigger {
+    pub name: String,
+    pub stream: Option<String>,
+    pub keepalive: bool,
+    pub descriptor: BTreeMap<String, String>,
+    pub attributes: BTreeMap<String, String>,
+}
+
+/// Parsed `launchctl print <domain>` output.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct DomainInfo {
+    pub name: String,
+    pub domain_type: Option<String>,
+    pub services: Vec<DomainService>,
+    /// label -> disabled, from the `disabled services` override block
+    pub disabled_services: BTreeMap<String, bool>,
+    pub endpoints: Vec<Endpoint>,
+    pub unmanaged_processes: Vec<UnmanagedProcess>,
+    pub subdomains: Vec<String>,
+    pub properties: Vec<String>,
+    /// Scalar fields not modelled above, keyed by their launchctl name
+    pub other: BTreeMap<String, String>,
+}
+
+/// One row of a domain's `services` block.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DomainService {
+    pub pid: Option<u32>,
+    pub last_exit_code: Option<String>,
+    pub label: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct UnmanagedProcess {
+    pub name: String,
+    pub endpoints: Vec<Endpoint>,
+    pub attributes: BTreeMap<String, String>,
+}
@@ -0,0 +1,194 @@
+//! `--watch` for `list` and `status`: redraw a command's output in place every interval.
+//!
+//! Each tick a command renders a fresh frame. Jobs whose pid or last exit code moved since the
+//! previous tick are highlighted by how they moved, which is what makes a flapping KeepAlive
+//! job stand out.
+
+use crate::error::{Result, UserError};
+use crate::output;
+use crate::styles::styles;
+use comfy_table::Cell;
+use crossterm::cursor::MoveTo;
+use crossterm::style::{Color, Print};
+use crossterm::terminal::{Clear, ClearType};
+use crossterm::{execute, queue};
+use std::io::Write;
+use std::time::Duration;
+
+/// Seconds between redraws when `--watch` is given without a value.
+pub const DEFAULT_INTERVAL: &str = "2";
+
+/// The runtime state of a job that watch mode compares between ticks.
+#[derive(Debug, Clone, PartialEq)]
+pub struct JobState {
+    pub pid: Option<u32>,
+    pub last_exit_code: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Transition {
+    Started,
+    Crashed,
+    Exited,
+}
+
+impl JobState {
+    pub fn new(pid: Option<u32>, last_exit_code: Option<String>) -> Self {
+        Self { pid, last_exit_code }
+    }
+
+    /// How the job moved between `self` and `next`, if it did. A job that died and was
+    /// respawned within one tick shows up as started, with a new pid.
+    pub fn transition(&self, next: &JobState) -> Option<Transition> {
+        let exited = next.pid.is_none()
+            && (self.pid.is_some() || next.last_exit_code != self.last_exit_code);
+        match () {
+            _ if next.pid.is_some() && next.pid != self.pid => Some(Transition::Started),
+            _ if exited => match next.last_exit_code.as_deref().is_none_or(is_clean_exit) {
+                true => Some(Transition::Exited),
+                false => Some(Transition::Crashed),
+            },
+            _ => None,
+        }
+    }
+}
+
+/// `launchctl` reports exit codes as `0`, `-` or `(never exited)` when there's nothing wrong,
+/// and as e.g. `78: Function not implemented` or `-9` otherwise.
+fn is_clean_exit(code: &str) -> bool {
+    matches!(code.split(':').next().unwrap_or_default().trim(), "0" | "-" | "(never exited)")
+}
+
+impl Transition {
+    /// The transition's name in its highlight color.
+    pub fn describe(self) -> String {
+        let label = match self {
+            Transition::Started => "started",
+            Transition::Crashed => "crashed",
+            Transition::Exited => "exited",
+        };
+        styles::prefix(self.color(), label)
+    }
+
+    fn color(self) -> Color {
+        match self {
+            Transition::Started => Color::Green,
+            Transition::Crashed => Color::Red,
+            Transition::Exited => Color::Blue,
+        }
+    }
+
+    fn cell_color(self) -> comfy_table::Color {
+        match self {
+            Transition::Started => comfy_table::Color::Green,
+            Transition::Crashed => comfy_table::Color::Red,
+            Transition::Exited => comfy_table::Color::Blue,
+        }
+    }
+}
+
+/// Table cells for one row, colored by `transition` when the row changed this tick.
+pub fn row(cells: Vec<String>, transition: Option<Transition>) -> Vec<Cell> {
+    cells
+        .into_iter()
+        .map(|text| match transition {
+            Some(transition) => Cell::new(text).fg(transition.cell_color()),
+            None => Cell::new(text),
+        })
+        .collect()
+}
+
+/// Redraw `frame` every `interval` seconds until interrupted.
+pub fn run(title: &str, interval: f64, mut frame: impl FnMut() -> Result<String>) -> Result<()> {
+    if output::is_structured() {
+        return Err(
+            UserError::InvalidArgument("--watch only works with table output".to_string()).into()
+        );
+    }
+    let interval = Duration::try_from_secs_f64(interval)
+        .ok()
+        .filter(|interval| !interval.is_zero())
+        .ok_or_else(|| {
+            UserError::InvalidArgument(format!("invalid --watch interval: {}", interval))
+        })?;
+
+    let legend = [Transition::Started, Transition::Crashed, Transition::Exited]
+        .map(Transition::describe)
+        .join(" ");
+    let header = format!(
+        "{}  {}  {}",
+        styles::prefix(Color::Blue, title),
+        styles::command(&format!("every {:?}, Ctrl-C to stop", interval)),
+        legend
+    );
+
+    let mut out = std::io::stdout();
+    execute!(out, Clear(ClearType::All))?;
+    loop {
+        let body = frame()?;
+        // overwrite line by line rather than clearing the screen first, which flickers
+        queue!(out, MoveTo(0, 0))?;
+        for line in std::iter::once(header.as_str()).chain(std::iter::once("")).chain(body.lines())
+        {
+            queue!(out, Print(line), Clear(ClearType::UntilNewLine), Print("\n"))?;
+        }
+        queue!(out, Clear(ClearType::FromCursorDown))?;
+        out.flush()?;
+        std::thread::sleep(interval);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state(pid: Option<u32>, last_exit_code: Option<&str>) -> JobState {
+        JobState::new(pid, last_exit_code.map(String::from))
+    }
+
+    #[test]
+    fn transitions() {
+        use Transition::*;
+
+        let cases = [
+            // nothing moved
+            (state(None, None), state(None, None), None),
+            (state(Some(42), Some("0")), state(Some(42), Some("0")), None),
+            (state(None, Some("1")), state(None, Some("1")), None),
+            (state(Some(42), Some("0")), state(Some(42), Some("-9")), None),
+            // came up, or was respawned with a new pid within one tick
+            (state(None, None), state(Some(42), None), Some(Started)),
+            (state(None, Some("1")), state(Some(42), Some("1")), Some(Started)),
+            (state(Some(42), Some("0")), state(Some(43), Some("-9")), Some(Started)),
+            // went down cleanly
+            (state(Some(42), None), state(None, None), Some(Exited)),
+            (state(Some(42), Some("0")), state(None, Some("0")), Some(Exited)),
+            (state(Some(42), Some("-")), state(None, Some("-")), Some(Exited)),
+            (state(Some(42), None), state(None, Some("(never exited)")), Some(Exited)),
+            // went down with a failure
+            (state(Some(42), Some("0")), state(None, Some("1")), Some(Crashed)),
+            (state(Some(42), Some("0")), state(None, Some("-9")), Some(Crashed)),
+            (
+                state(Some(42), None),
+                state(None, Some("78: Function not implemented")),
+                Some(Crashed),
+            ),
+            // ran and exited entirely between two ticks
+            (state(None, Some("0")), state(None, Some("1")), Some(Crashed)),
+            (state(None, Some("1")), state(None, Some("0")), Some(Exited)),
+        ];
+        for (previous, next, expected) in cases {
+            assert_eq!(previous.transition(&next), expected, "{:?} -> {:?}", previous, next);
+        }
+    }
+
+    #[test]
+    fn clean_exit_codes() {
+        for code in ["0", "-", "(never exited)", "0: Undefined error: 0"] {
+            assert!(is_clean_exit(code), "{}", code);
+        }
+        for code in ["1", "-9", "-15", "78: Function not implemented", ""] {
+            assert!(!is_clean_exit(code), "{}", code);
+        }
+    }
+}
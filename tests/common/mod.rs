@@ -0,0 +1,105 @@
+//! The fixture shared by the integration tests: a temp root, a `FakeLaunchctl` and a way to
+//! run real commands against them.
+// Each test binary uses a different part of this
+#![allow(dead_code)]
+
+use angel::angel::Angel;
+use angel::cli::Cli;
+use angel::commands;
+use angel::config::Config;
+use angel::daemon::DaemonRegistry;
+use angel::error::Result;
+use angel::launchctl::{self, FakeLaunchctl};
+use angel::output::{self, OutputFormat};
+use angel::paths::Paths;
+use angel::prompt;
+use angel::types::{Daemon, Plist};
+use clap::Parser;
+use nix::unistd;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+/// The launchctl backend is process-wide, so tests take turns.
+static SERIAL: Mutex<()> = Mutex::new(());
+
+pub struct Fixture {
+    pub root: PathBuf,
+    pub uid: u32,
+    pub fake: Arc<FakeLaunchctl>,
+    _serial: MutexGuard<'static, ()>,
+}
+
+impl Fixture {
+    /// Prompts are answered as with `--yes`, or `--no-input` when `yes` is false. The prompt
+    /// mode is set once per process, so it must be the same for every test in a binary.
+    pub fn new(yes: bool) -> Self {
+        static COUNTER: AtomicUsize = AtomicUsize::new(0);
+        let serial = SERIAL.lock().unwrap_or_else(|e| e.into_inner());
+        let root = std::env::temp_dir().join(format!(
+            "angel-it-{}-{}",
+            std::process::id(),
+            COUNTER.fetch_add(1, Ordering::SeqCst)
+        ));
+        std::fs::create_dir_all(root.join("home/tester/Library/LaunchAgents")).unwrap();
+        std::fs::create_dir_all(root.join("Library/LaunchDaemons")).unwrap();
+
+        let uid = unistd::getuid().as_raw();
+        let fake = Arc::new(
+            FakeLaunchctl::new()
+                .with_domain(&format!("gui/{}", uid))
+                .with_domain(&format!("user/{}", uid)),
+        );
+        launchctl::set_backend(fake.clone());
+        output::init(false, OutputFormat::Table);
+        prompt::init(yes, !yes);
+        Self { root, uid, fake, _serial: serial }
+    }
+
+    /// A fresh `Angel`, the way `main` loads one: commands see the registry as of this call.
+    pub fn angel(&self) -> Angel {
+        let config = Config { root: None, directories: None, colors: None };
+        let paths = Paths::new(&self.root, Some(PathBuf::from("/home/tester")));
+        let daemons = DaemonRegistry::new(&config, &paths, self.uid).unwrap();
+        let uid = unistd::Uid::from_raw(self.uid);
+        Angel { daemons, config, paths, euid: unistd::geteuid(), uid }
+    }
+
+    pub fn run(&self, args: &[&str]) -> Result<()> {
+        let cli = Cli::try_parse_from(std::iter::once("angel").chain(args.iter().copied()))
+            .unwrap_or_else(|e| panic!("{}", e));
+        commands::run(&self.angel(), cli.command)
+    }
+
+    pub fn daemon(&self, name: &str) -> Daemon {
+        self.angel().daemons.get_match(name, true, None).unwrap().clone()
+    }
+
+    pub fn user_agents(&self) -> PathBuf {
+        self.root.join("home/tester/Library/LaunchAgents")
+    }
+
+    pub fn gui(&self, label: &str) -> String {
+        format!("gui/{}/{}", self.uid, label)
+    }
+
+    pub fn user(&self, label: &str) -> String {
+        format!("user/{}/{}", self.uid, label)
+    }
+}
+
+impl Drop for Fixture {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.root);
+    }
+}
+
+pub fn write_plist(path: &Path, label: &str, run_at_load: bool) {
+    let plist = Plist {
+        label: Some(label.to_string()),
+        program_arguments: Some(vec!["/usr/bin/true".to_string()]),
+        run_at_load: Some(run_at_load),
+        ..Default::default()
+    };
+    plist::to_file_xml(path, &plist).unwrap();
+}
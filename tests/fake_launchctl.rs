@@ -0,0 +1,191 @@
+//! Runs real commands end to end, against a `FakeLaunchctl` and a fixture tree under a temp root.
+
+mod common;
+
+use angel::launchctl::{FakeService, LaunchctlBackend};
+use angel::types::{Domain, Plist};
+use common::{Fixture, write_plist};
+use nix::unistd;
+
+#[test]
+fn registry_reflects_the_fake_runtime() {
+    let fx = Fixture::new(true);
+    let path = fx.root.join("Library/LaunchDaemons/com.example.daemon.plist");
+    write_plist(&path, "com.example.daemon", false);
+    fx.fake.add_service(
+        "system",
+        FakeService {
+            label: "com.example.daemon".to_string(),
+            path: Some(path.clone()),
+            pid: Some(42),
+            ..Default::default()
+        },
+    );
+    fx.fake.add_service(
+        &format!("gui/{}", fx.uid),
+        FakeService {
+            label: "com.example.dynamic".to_string(),
+            last_exit_code: Some(1),
+            ..Default::default()
+        },
+    );
+
+    let daemon = fx.daemon("com.example.daemon");
+    assert_eq!(daemon.domain, Domain::System);
+    assert_eq!(daemon.source_path, Some(path));
+    assert_eq!(daemon.pid, Some(42));
+
+    let dynamic = fx.daemon("com.example.dynamic");
+    assert_eq!(dynamic.domain, Domain::Gui(fx.uid));
+    assert_eq!(dynamic.source_path, None);
+    assert_eq!(dynamic.pid, None);
+    assert_eq!(dynamic.last_exit_code.as_deref(), Some("1"));
+
+    fx.run(&["list"]).unwrap();
+    fx.run(&["status", "com.example.daemon", "--exact"]).unwrap();
+}
+
+#[test]
+fn service_lifecycle() {
+    let fx = Fixture::new(true);
+    let label = "com.example.agent";
+    write_plist(&fx.user_agents().join("com.example.agent.plist"), label, true);
+
+    fx.run(&["bootstrap", label, "--exact"]).unwrap();
+    let service = fx.fake.service(&fx.user(label)).expect("bootstrapped into user domain");
+    assert!(service.pid.is_some());
+    assert!(fx.daemon(label).pid.is_some());
+
+    fx.run(&["stop", label, "--exact"]).unwrap();
+    let service = fx.fake.service(&fx.user(label)).unwrap();
+    assert_eq!(service.pid, None);
+    assert_eq!(service.last_exit_code, Some(-15));
+    assert_eq!(fx.daemon(label).pid, None);
+
+    fx.run(&["start", label, "--exact"]).unwrap();
+    assert!(fx.fake.service(&fx.user(label)).unwrap().pid.is_some());
+
+    fx.run(&["disable", label, "--exact"]).unwrap();
+    assert!(fx.fake.is_disabled(&fx.user(label)));
+    assert_eq!(fx.daemon(label).disabled, Some(true));
+
+    fx.run(&["enable", label, "--exact"]).unwrap();
+    assert!(!fx.fake.is_disabled(&fx.user(label)));
+    assert_eq!(fx.daemon(label).disabled, Some(false));
+
+    fx.run(&["bootout", label, "--exact"]).unwrap();
+    assert!(fx.fake.service(&fx.user(label)).is_none());
+    assert!(fx.run(&["start", label, "--exact"]).is_err());
+}
+
+#[test]
+fn install_and_uninstall_restore_a_moved_plist() {
+    let fx = Fixture::new(true);
+    let label = "com.example.moved";
+    let source = fx.root.join("com.example.moved.plist");
+    write_plist(&source, label, false);
+
+    fx.run(&["install", source.to_str().unwrap(), "--strategy", "move", "--domain", "gui"])
+        .unwrap();
+    let target = fx.user_agents().join("com.example.moved.plist");
+    assert!(!source.exists());
+    assert!(target.exists());
+    let service = fx.fake.service(&fx.gui(label)).expect("installed into gui domain");
+    assert_eq!(service.path, Some(target.clone()));
+
+    let daemon = fx.daemon(label);
+    assert_eq!(daemon.domain, Domain::Gui(fx.uid));
+
+    fx.run(&["uninstall", label, "--exact"]).unwrap();
+    assert!(fx.fake.service(&fx.gui(label)).is_none());
+    assert!(source.exists());
+    assert!(!target.exists());
+}
+
+#[test]
+fn failed_install_is_undone_and_not_recorded() {
+    let fx = Fixture::new(true);
+    let label = "com.example.refused";
+    let source = fx.root.join("com.example.refused.plist");
+    let target = fx.user_agents().join("com.example.refused.plist");
+    write_plist(&source, label, false);
+    let contents = std::fs::read(&source).unwrap();
+    // launchd refuses to bootstrap a disabled service
+    fx.fake.exec(&["disable", &fx.gui(label)]).unwrap();
+
+    for strategy in ["symlink", "copy", "move"] {
+        let result = fx.run(&[
+            "install",
+            source.to_str().unwrap(),
+            "--strategy",
+            strategy,
+            "--domain",
+            "gui",
+        ]);
+        assert!(result.is_err(), "{}", strategy);
+        assert!(fx.fake.service(&fx.gui(label)).is_none());
+        assert_eq!(std::fs::read(&source).unwrap(), contents, "{}", strategy);
+        assert!(std::fs::symlink_metadata(&target).is_err(), "{}", strategy);
+        assert!(!fx.root.join("home/tester/.config/angel/installed.yaml").exists());
+    }
+}
+
+#[test]
+fn new_writes_and_bootstraps_a_plist() {
+    let fx = Fixture::new(true);
+    let label = "com.example.new";
+    fx.run(&[
+        "new",
+        label,
+        "--program",
+        "/usr/bin/true",
+        "--run-at-load",
+        "--domain",
+        "gui",
+        "--bootstrap",
+    ])
+    .unwrap();
+
+    let path = fx.root.join("home/tester/.config/angel/gui/com.example.new.plist");
+    let plist: Plist = plist::from_file(&path).unwrap();
+    assert_eq!(plist.label.as_deref(), Some(label));
+    assert_eq!(plist.program.as_deref(), Some("/usr/bin/true"));
+    assert_eq!(plist.run_at_load, Some(true));
+
+    let service = fx.fake.service(&fx.gui(label)).expect("bootstrapped into gui domain");
+    assert_eq!(service.path, Some(path));
+    assert!(service.pid.is_some());
+}
+
+#[test]
+fn failed_new_bootstrap_is_an_error() {
+    let fx = Fixture::new(true);
+    let label = "com.example.refused-new";
+    fx.fake.exec(&["disable", &fx.gui(label)]).unwrap();
+
+    let result =
+        fx.run(&["new", label, "--program", "/usr/bin/true", "--domain", "gui", "--bootstrap"]);
+    assert!(result.is_err());
+    assert!(fx.fake.service(&fx.gui(label)).is_none());
+    // The plist is still written, ready for `angel bootstrap` once the service is enabled
+    assert!(fx.root.join("home/tester/.config/angel/gui/com.example.refused-new.plist").exists());
+}
+
+#[test]
+fn unreadable_plist_directories_are_diagnosed() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let fx = Fixture::new(true);
+    // Root reads the directory regardless of its mode
+    if unistd::geteuid().is_root() {
+        return;
+    }
+    let agents = fx.user_agents();
+    write_plist(&agents.join("com.example.hidden.plist"), "com.example.hidden", false);
+    std::fs::set_permissions(&agents, std::fs::Permissions::from_mode(0o000)).unwrap();
+
+    let angel = fx.angel();
+    std::fs::set_permissions(&agents, std::fs::Permissions::from_mode(0o755)).unwrap();
+    let diagnostic = angel.daemons.diagnostics().iter().find(|d| d.path == agents);
+    assert!(diagnostic.is_some(), "{:?}", angel.daemons.diagnostics());
+}
@@ -0,0 +1,35 @@
+//! Runs commands as with `--no-input`, where confirmations take their default answer.
+
+mod common;
+
+use common::{Fixture, write_plist};
+
+#[test]
+fn install_does_not_overwrite_an_existing_plist() {
+    let fx = Fixture::new(false);
+    let label = "com.example.existing";
+    let source = fx.root.join("com.example.existing.plist");
+    write_plist(&source, label, true);
+    let target = fx.user_agents().join("com.example.existing.plist");
+    write_plist(&target, label, false);
+    let before = std::fs::read(&target).unwrap();
+
+    assert!(fx.run(&["install", source.to_str().unwrap(), "--domain", "gui"]).is_err());
+    assert_eq!(std::fs::read(&target).unwrap(), before);
+    assert!(source.exists());
+    assert!(fx.fake.service(&fx.gui(label)).is_none());
+}
+
+#[test]
+fn new_does_not_overwrite_an_existing_plist() {
+    let fx = Fixture::new(false);
+    let label = "com.example.existing";
+    let target = fx.root.join("home/tester/.config/angel/gui/com.example.existing.plist");
+    std::fs::create_dir_all(target.parent().unwrap()).unwrap();
+    write_plist(&target, label, false);
+    let before = std::fs::read(&target).unwrap();
+
+    let result = fx.run(&["new", label, "--program", "/bin/sh", "--domain", "gui"]);
+    assert!(result.is_err());
+    assert_eq!(std::fs::read(&target).unwrap(), before);
+}